@@ -0,0 +1,13 @@
+//! Checking command for datasets.
+
+pub use super::*;
+
+/// Check the dataset for inconsistencies.
+#[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+#[command(next_line_help = true)]
+pub struct CheckArguments {
+    /// Common arguments for datasets.
+    #[command(flatten)]
+    pub common_arguments: DatasetCommonArguments,
+}
@@ -0,0 +1,24 @@
+//! Exporting command for datasets.
+
+pub use super::*;
+
+/// Export the dataset to another format.
+#[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+#[command(next_line_help = true)]
+pub struct ExportArguments {
+    /// Exported dataset directory path.
+    /// The images are copied into its image sub-directory.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, short, value_name = "Path")]
+    pub output_path: PathBuf,
+
+    /// Exported dataset type.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, short, value_name = "Type", default_value = "nerfstudio")]
+    pub format: SourceType,
+
+    /// Common arguments for datasets.
+    #[command(flatten)]
+    pub common_arguments: DatasetCommonArguments,
+}
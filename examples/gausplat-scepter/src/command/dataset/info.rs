@@ -0,0 +1,24 @@
+//! Inspection command for datasets.
+
+pub use super::*;
+
+/// Show the summary of the dataset.
+#[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+#[command(next_line_help = true)]
+pub struct InfoArguments {
+    /// Enabling evaluation mode.
+    /// It shows the splitting of the testing dataset.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, short, default_value_t = false)]
+    pub eval: bool,
+
+    /// Printing the summary in JSON.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+
+    /// Common arguments for datasets.
+    #[command(flatten)]
+    pub common_arguments: DatasetCommonArguments,
+}
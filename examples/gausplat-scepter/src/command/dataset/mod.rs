@@ -0,0 +1,54 @@
+//! Command for datasets.
+
+pub mod check;
+pub mod export;
+pub mod info;
+
+pub use super::*;
+pub use check::*;
+pub use export::*;
+pub use gaussian_3d::SourceType;
+pub use info::*;
+
+/// Run tasks for datasets.
+#[derive(Clone, Debug, Deserialize, PartialEq, Parser, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+pub enum DatasetCommand {
+    /// Check the dataset for inconsistencies.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[serde(rename = "check")]
+    Check(CheckArguments),
+
+    /// Export the dataset to another format.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[serde(rename = "export")]
+    Export(ExportArguments),
+
+    /// Show the summary of the dataset.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[serde(rename = "info")]
+    Info(InfoArguments),
+}
+
+/// Common arguments for datasets.
+#[derive(Clone, Debug, Deserialize, Serialize, Parser, PartialEq)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+pub struct DatasetCommonArguments {
+    /// Dataset directory path.
+    /// It may include prior and image files.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(index = 1, value_name = "Path")]
+    pub source_path: PathBuf,
+
+    /// Dataset type.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, short = 't', value_name = "Path", default_value = "colmap")]
+    pub source_type: SourceType,
+
+    /// Dataset image sub-directory path.
+    /// It may be included in the dataset directory.
+    /// The frame file paths of Nerfstudio are relative to the dataset directory instead.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, short, value_name = "Path", default_value = "images")]
+    pub images: PathBuf,
+}
@@ -0,0 +1,88 @@
+//! Benchmarking command for 3DGS.
+
+pub use super::*;
+
+use gausplat::renderer::render::gaussian_3d::Gaussian3dRenderOptions;
+
+/// Benchmark the rendering for 3DGS.
+#[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+#[command(next_line_help = true)]
+pub struct BenchArguments {
+    /// Iteration for benchmarking.
+    /// It refers to the model saving iteration.
+    /// [default: Best saving iteration if recorded, otherwise maximum saving iteration]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    pub iteration: Option<u64>,
+
+    /// Number of frames for warming up.
+    /// They are rendered before timing at each scale.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", default_value_t = 5)]
+    pub warmup: u64,
+
+    /// Number of passes over the cameras at each scale.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", default_value_t = 3)]
+    pub repeat: u64,
+
+    /// Scales of the image resolution.
+    /// The scale of 1.0 refers to the native resolution.
+    /// The scales above 1.0 upscale the images, e.g., for supersampling.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64", num_args = 1.., default_values_t = [1.0, 0.5])]
+    pub scales: Vec<f64>,
+
+    /// Color SH feature degree.
+    /// It generally ranges from 0 to 3.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(
+        long, value_name = "U32",
+        default_value_t = Gaussian3dRenderOptions::new().colors_sh_degree_max,
+    )]
+    pub sh_degree: u32,
+
+    /// Device for rendering.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Device", default_value = "gpu")]
+    pub device: BenchDevice,
+
+    /// Timing the stages of each frame.
+    /// The stages are submitting, executing and reading back.
+    /// The renderer runs projection, sorting and rasterization in one submission,
+    /// so they are not timed separately.
+    /// Timing them requires the timestamp queries in the renderer.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, default_value_t = false)]
+    #[serde(default)]
+    pub stages: bool,
+
+    /// Printing the results in JSON.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+
+    /// Common arguments for 3DGS.
+    #[command(flatten)]
+    pub common_arguments: Gaussian3dCommonArguments,
+}
+
+/// Device for benchmarking.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ValueEnum,
+)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum BenchDevice {
+    /// The default GPU.
+    #[default]
+    #[serde(rename = "gpu")]
+    Gpu,
+
+    /// The software adapter of WebGPU running on the CPU, e.g., llvmpipe or WARP.
+    /// The renderer has no native CPU backend, since its kernels target WebGPU.
+    /// It requires a software adapter installed on the system.
+    #[value(verbatim_doc_comment)]
+    #[serde(rename = "software")]
+    Software,
+}
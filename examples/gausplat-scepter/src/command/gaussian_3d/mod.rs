@@ -1,11 +1,15 @@
 //! Command for 3DGS.
 
+pub mod bench;
 pub mod eval;
+pub mod pipeline;
 pub mod render;
 pub mod train;
 
 pub use super::*;
+pub use bench::*;
 pub use eval::*;
+pub use pipeline::*;
 pub use render::*;
 pub use train::*;
 
@@ -15,11 +19,21 @@ use clap::ArgAction;
 #[derive(Clone, Debug, Deserialize, PartialEq, Parser, Serialize)]
 #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
 pub enum Gaussian3dModelCommand {
+    /// Benchmark the rendering for 3DGS.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[serde(rename = "bench")]
+    Bench(BenchArguments),
+
     /// Evaluate for 3DGS.
     #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
     #[serde(rename = "eval")]
     Eval(EvalArguments),
 
+    /// Train, render and evaluate for 3DGS.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[serde(rename = "pipeline")]
+    Pipeline(PipelineArguments),
+
     /// Render for 3DGS.
     #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
     #[serde(rename = "render")]
@@ -54,6 +68,7 @@ pub struct Gaussian3dCommonArguments {
 
     /// Dataset image sub-directory path.
     /// It may be included in the dataset directory.
+    /// The frame file paths of Nerfstudio are relative to the dataset directory instead.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(long, short, value_name = "Path", default_value = "images")]
     pub images: PathBuf,
@@ -68,11 +83,61 @@ pub struct Gaussian3dCommonArguments {
     #[arg(long, short, default_value_t = false)]
     pub eval: bool,
 
+    /// Normalizing the scene with the up axis.
+    /// The scene is centered at the cameras, scaled to a unit radius,
+    /// and rotated so that the average camera up vector is the axis.
+    /// The transform is saved as 'scene_transform.json' in the model directory
+    /// while training, and the models are also saved as 'point_cloud_dataset.ply'
+    /// in the dataset frame.
+    /// Rendering and benchmarking apply the saved transform instead.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Axis")]
+    #[serde(default)]
+    pub normalize: Option<UpAxis>,
+
     /// Disabling messages.
     /// The more quiet flags are set, the less messages are shown.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(long, short, action = ArgAction::Count, default_value_t = 0)]
     pub quiet: u8,
+
+    /// Format of the progress.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Format", default_value = "bar")]
+    #[serde(default)]
+    pub progress: ProgressFormat,
+
+    /// File path of the JSON progress events.
+    /// The events are appended to the file.
+    /// [default: Standard output]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Path")]
+    #[serde(default)]
+    pub progress_file: Option<PathBuf>,
+
+    /// Number of iterations between the JSON progress events of training.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", default_value_t = default_progress_interval())]
+    #[serde(default = "default_progress_interval")]
+    pub progress_interval: u64,
+}
+
+/// Format of the progress.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ValueEnum,
+)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum ProgressFormat {
+    /// The progress bar for humans.
+    #[default]
+    #[serde(rename = "bar")]
+    Bar,
+
+    /// The newline-delimited JSON events for programs.
+    /// Each event has the fields of 'event', 'time' and 'model_path'.
+    #[value(verbatim_doc_comment)]
+    #[serde(rename = "json")]
+    Json,
 }
 
 /// Dataset type.
@@ -89,4 +154,35 @@ pub enum SourceType {
     #[value(verbatim_doc_comment)]
     #[serde(rename = "colmap")]
     Colmap,
+
+    /// The dataset is built from Nerfstudio.
+    /// It generally includes the following files:
+    /// 1. transforms.json
+    /// 2. sparse_pc.ply (optional, as 'ply_file_path')
+    /// 3. images/*.jpg
+    #[value(verbatim_doc_comment)]
+    #[serde(rename = "nerfstudio")]
+    Nerfstudio,
+}
+
+/// Up axis of the scene.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ValueEnum,
+)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum UpAxis {
+    /// The positive Y axis.
+    #[default]
+    #[serde(rename = "y")]
+    Y,
+
+    /// The positive Z axis.
+    #[serde(rename = "z")]
+    Z,
+}
+
+/// Return the default value of [`Gaussian3dCommonArguments::progress_interval`].
+#[inline]
+fn default_progress_interval() -> u64 {
+    100
 }
@@ -0,0 +1,24 @@
+//! Pipeline command for 3DGS.
+
+pub use super::*;
+
+/// Train, render and evaluate for 3DGS.
+///
+/// The best iteration of early stopping is rendered and evaluated if recorded,
+/// otherwise the final iteration.
+/// The summary is saved as 'summary.json' in the model directory
+/// and printed as a JSON line to the standard error.
+#[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+#[command(next_line_help = true)]
+pub struct PipelineArguments {
+    /// Do not render the testing dataset.
+    /// The metrics are still evaluated.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, default_value_t = false)]
+    pub skip_render: bool,
+
+    /// Arguments for training.
+    #[command(flatten)]
+    pub train_arguments: TrainArguments,
+}
@@ -11,7 +11,7 @@ use gausplat::renderer::render::gaussian_3d::Gaussian3dRenderOptions;
 pub struct RenderArguments {
     /// Iteration for rendering.
     /// It refers to the model saving iteration.
-    /// [default: Maximum saving iteration]
+    /// [default: Best saving iteration if recorded, otherwise maximum saving iteration]
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(long, value_name = "U64")]
     pub iteration: Option<u64>,
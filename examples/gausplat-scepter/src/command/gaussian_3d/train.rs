@@ -2,9 +2,10 @@
 
 pub use super::*;
 
+use color_eyre::eyre::eyre;
 use gausplat::trainer::train::gaussian_3d::Gaussian3dTrainerConfig;
-use gausplat::trainer::train::gaussian_3d::RefinerConfig;
-use std::sync::LazyLock;
+use gausplat::trainer::train::gaussian_3d::{RefinerConfig, SEED};
+use std::{fmt, str::FromStr, sync::LazyLock};
 
 /// Train for 3DGS.
 #[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
@@ -21,6 +22,19 @@ pub struct TrainArguments {
     #[command(flatten)]
     pub common_arguments: Gaussian3dCommonArguments,
 
+    /// Strategies for initializing the scene.
+    /// It is in the form of '<KIND>[:count=<U64>,opacity=<F64>,scale=<RULE>]'.
+    /// KIND: 'sfm', 'box', or 'sphere'.
+    /// RULE: 'neighbor' or a constant scaling in world units.
+    /// If it is set multiple times, the union of the points is used.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(
+        long, value_name = "Strategy", num_args = 1..,
+        default_values_t = default_init(),
+    )]
+    #[serde(default = "default_init")]
+    pub init: Vec<InitStrategy>,
+
     /// Iterations for testing.
     /// It may take few second to run.
     /// If '-qq' is set, no test will be performed.
@@ -72,6 +86,12 @@ pub struct TrainArguments {
     )]
     pub position_lr_max_steps: u64,
 
+    /// Learning rate schedule for position.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Schedule", default_value = "exponential")]
+    #[serde(default)]
+    pub position_lr_schedule: LearningRateSchedule,
+
     /// Learning rate for color SH feature.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(
@@ -80,6 +100,27 @@ pub struct TrainArguments {
     )]
     pub feature_lr: f64,
 
+    /// Learning rate at final for color SH feature.
+    /// [default: Learning rate at initial for color SH feature]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64")]
+    #[serde(default)]
+    pub feature_lr_final: Option<f64>,
+
+    /// Learning rate maximum step count for color SH feature.
+    /// It only takes effect if the final learning rate is set.
+    /// [default: Number of iterations for training]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    #[serde(default)]
+    pub feature_lr_max_steps: Option<u64>,
+
+    /// Learning rate schedule for color SH feature.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Schedule", default_value = "exponential")]
+    #[serde(default)]
+    pub feature_lr_schedule: LearningRateSchedule,
+
     /// Learning rate for opacity.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(
@@ -88,6 +129,27 @@ pub struct TrainArguments {
     )]
     pub opacity_lr: f64,
 
+    /// Learning rate at final for opacity.
+    /// [default: Learning rate at initial for opacity]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64")]
+    #[serde(default)]
+    pub opacity_lr_final: Option<f64>,
+
+    /// Learning rate maximum step count for opacity.
+    /// It only takes effect if the final learning rate is set.
+    /// [default: Number of iterations for training]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    #[serde(default)]
+    pub opacity_lr_max_steps: Option<u64>,
+
+    /// Learning rate schedule for opacity.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Schedule", default_value = "exponential")]
+    #[serde(default)]
+    pub opacity_lr_schedule: LearningRateSchedule,
+
     /// Learning rate for scaling.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(
@@ -96,6 +158,27 @@ pub struct TrainArguments {
     )]
     pub scaling_lr: f64,
 
+    /// Learning rate at final for scaling.
+    /// [default: Learning rate at initial for scaling]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64")]
+    #[serde(default)]
+    pub scaling_lr_final: Option<f64>,
+
+    /// Learning rate maximum step count for scaling.
+    /// It only takes effect if the final learning rate is set.
+    /// [default: Number of iterations for training]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    #[serde(default)]
+    pub scaling_lr_max_steps: Option<u64>,
+
+    /// Learning rate schedule for scaling.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Schedule", default_value = "exponential")]
+    #[serde(default)]
+    pub scaling_lr_schedule: LearningRateSchedule,
+
     /// Learning rate for rotation.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
     #[arg(
@@ -104,6 +187,49 @@ pub struct TrainArguments {
     )]
     pub rotation_lr: f64,
 
+    /// Learning rate at final for rotation.
+    /// [default: Learning rate at initial for rotation]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64")]
+    #[serde(default)]
+    pub rotation_lr_final: Option<f64>,
+
+    /// Learning rate maximum step count for rotation.
+    /// It only takes effect if the final learning rate is set.
+    /// [default: Number of iterations for training]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    #[serde(default)]
+    pub rotation_lr_max_steps: Option<u64>,
+
+    /// Learning rate schedule for rotation.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Schedule", default_value = "exponential")]
+    #[serde(default)]
+    pub rotation_lr_schedule: LearningRateSchedule,
+
+    /// Number of warm-up iterations for the learning rates.
+    /// The learning rates increase linearly from zero during the warm-up.
+    /// It only takes effect for the schedules of 'cosine' and 'linear'.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", default_value_t = 0)]
+    #[serde(default)]
+    pub lr_warmup_steps: u64,
+
+    /// Iterations for decaying the learning rates by '--lr_gamma'.
+    /// It only takes effect for the schedule of 'step'.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", num_args = 0..)]
+    #[serde(default)]
+    pub lr_milestones: Vec<u64>,
+
+    /// Decaying factor for the learning rates at each milestone.
+    /// It only takes effect for the schedule of 'step'.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64", default_value_t = default_lr_gamma())]
+    #[serde(default = "default_lr_gamma")]
+    pub lr_gamma: f64,
+
     /// Tolerance for scalings.
     /// It may affect the model quality and size.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
@@ -153,6 +279,16 @@ pub struct TrainArguments {
     )]
     pub densify_grad_threshold: f64,
 
+    /// Maximum number of points in the scene.
+    /// The densification pauses while the next one may exceed it,
+    /// and the pruning continues.
+    /// The projected size is logged before training and at each densification.
+    /// [default: Unlimited]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    #[serde(default)]
+    pub max_gaussians: Option<u64>,
+
     /// Tolerance for opacity.
     /// It may affect the model quality, time, and size.
     #[arg(verbatim_doc_comment, rename_all = "snake_case")]
@@ -179,8 +315,193 @@ pub struct TrainArguments {
             REFINER_CONFIG.range_increasing_colors_sh_degree_max.start,
     )]
     pub increase_sh_degree_from_iter: u64,
+
+    /// Sampling method of the training cameras.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Method", default_value = "random")]
+    #[serde(default)]
+    pub camera_sampling: CameraSampling,
+
+    /// Downscaling factors of the training images from coarse to fine.
+    /// The images are downscaled when first sampled, and only those of the
+    /// current factor are cached.
+    /// It may speed up the early iterations on large datasets.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(
+        long, value_name = "U32", num_args = 1..,
+        default_values_t = default_resolution_factors(),
+    )]
+    #[serde(default = "default_resolution_factors")]
+    pub resolution_factors: Vec<u32>,
+
+    /// Iterations for stepping up to the next downscaling factor.
+    /// It should have one less value than the factors.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", num_args = 0..)]
+    #[serde(default)]
+    pub resolution_iterations: Vec<u64>,
+
+    /// Maximum duration of training in seconds.
+    /// The model is saved and tested when the duration is reached.
+    /// It should be positive and finite.
+    /// [default: Unlimited]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "F64")]
+    #[serde(default)]
+    pub max_duration: Option<f64>,
+
+    /// Number of iterations between evaluations for early stopping.
+    /// The testing dataset is evaluated, so '--eval' should be set.
+    /// The model of the best iteration is saved.
+    /// [default: No early stopping]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64")]
+    #[serde(default)]
+    pub early_stopping_interval: Option<u64>,
+
+    /// Number of evaluations without improvement before early stopping.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", default_value_t = default_early_stopping_patience())]
+    #[serde(default = "default_early_stopping_patience")]
+    pub early_stopping_patience: u64,
+
+    /// Metric for early stopping.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Metric", default_value = "psnr")]
+    #[serde(default)]
+    pub early_stopping_metric: EarlyStoppingMetric,
+
+    /// Seed for randomness.
+    /// It is used by the camera sampling, scene initialization,
+    /// and the backend randomness of training, e.g., densification noise.
+    /// The GPU kernels may accumulate the gradients in varying orders,
+    /// so the trained models are not guaranteed to be bit-identical.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "U64", default_value_t = default_seed())]
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+
+    /// Preset of the arguments for a scene.
+    /// The explicit arguments override the preset.
+    /// The built-in presets are "playroom", "train" and "truck".
+    /// The user presets are the files "<Name>.{toml,yaml,yml,json}"
+    /// in "~/.config/gausplat/presets", which override the built-in ones.
+    /// The name only contains ASCII alphanumerics, '-' and '_'.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "Name")]
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+/// Metric for early stopping.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ValueEnum,
+)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum EarlyStoppingMetric {
+    /// Peak signal-to-noise ratio.
+    #[default]
+    #[serde(rename = "psnr")]
+    Psnr,
+
+    /// Mean structural similarity index.
+    #[serde(rename = "ssim")]
+    Ssim,
+}
+
+/// Learning rate schedule.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ValueEnum,
+)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum LearningRateSchedule {
+    /// Exponential decay from the initial to the final learning rate.
+    /// It is the schedule of the trainer.
+    #[default]
+    #[value(verbatim_doc_comment)]
+    #[serde(rename = "exponential")]
+    Exponential,
+
+    /// Cosine decay from the initial to the final learning rate after the warm-up.
+    #[serde(rename = "cosine")]
+    Cosine,
+
+    /// Linear decay from the initial to the final learning rate after the warm-up.
+    #[serde(rename = "linear")]
+    Linear,
+
+    /// Step decay from the initial learning rate at each milestone.
+    #[serde(rename = "step")]
+    Step,
+
+    /// Constant at the initial learning rate.
+    #[serde(rename = "constant")]
+    Constant,
+}
+
+/// Sampling method of the training cameras.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ValueEnum,
+)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum CameraSampling {
+    /// Sampling with replacement.
+    /// Some cameras may be visited more often than others.
+    #[default]
+    #[value(verbatim_doc_comment)]
+    #[serde(rename = "random")]
+    Random,
+
+    /// Sampling without replacement in every epoch.
+    /// Every camera is visited once before any camera is visited again.
+    #[value(verbatim_doc_comment)]
+    #[serde(rename = "epoch")]
+    Epoch,
 }
 
+/// Strategy for initializing the scene.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct InitStrategy {
+    /// Source of the points.
+    pub kind: InitKind,
+    /// Number of points.
+    /// It defaults to all SfM points or [`INIT_POINT_COUNT_DEFAULT`].
+    pub count: Option<u64>,
+    /// Opacity at initial.
+    /// It is in (0, 1) and defaults to the value chosen by the scene.
+    pub opacity: Option<f64>,
+    /// Rule of scaling at initial.
+    pub scale: InitScaleRule,
+}
+
+/// Source of the points at initialization.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+#[value(verbatim_doc_comment, rename_all = "snake_case")]
+pub enum InitKind {
+    /// The points from structure-from-motion, e.g. 'points3D.bin'.
+    #[default]
+    Sfm,
+    /// The points uniformly sampled in the bounding box of the camera frustums.
+    Box,
+    /// The points uniformly sampled on a sphere shell enclosing the scene.
+    /// It is helpful for distant backgrounds.
+    Sphere,
+}
+
+/// Rule of scaling at initialization.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum InitScaleRule {
+    /// The distance to the nearest neighbors.
+    #[default]
+    Neighbor,
+    /// The constant value in world units.
+    Constant(f64),
+}
+
+/// Number of points sampled at initialization if not specified.
+pub const INIT_POINT_COUNT_DEFAULT: u64 = 100000;
+
 /// [`Gaussian3dTrainerConfig::default()`]
 pub static TRAINER_CONFIG: LazyLock<Gaussian3dTrainerConfig> =
     LazyLock::new(Gaussian3dTrainerConfig::default);
@@ -188,3 +509,235 @@ pub static TRAINER_CONFIG: LazyLock<Gaussian3dTrainerConfig> =
 /// [`Gaussian3dTrainerConfig::default()`]
 pub static REFINER_CONFIG: LazyLock<RefinerConfig> =
     LazyLock::new(|| Gaussian3dTrainerConfig::default().refiner);
+
+/// Return the default value of [`TrainArguments::init`].
+#[inline]
+fn default_init() -> Vec<InitStrategy> {
+    vec![InitStrategy::default()]
+}
+
+/// Return the default value of [`TrainArguments::lr_gamma`].
+#[inline]
+fn default_lr_gamma() -> f64 {
+    0.1
+}
+
+/// Return the default value of [`TrainArguments::resolution_factors`].
+#[inline]
+fn default_resolution_factors() -> Vec<u32> {
+    vec![1]
+}
+
+/// Return the default value of [`TrainArguments::early_stopping_patience`].
+#[inline]
+fn default_early_stopping_patience() -> u64 {
+    3
+}
+
+/// Return the default value of [`TrainArguments::seed`].
+#[inline]
+fn default_seed() -> u64 {
+    SEED
+}
+
+impl fmt::Display for InitStrategy {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        // NOTE: The kind is always a valid value.
+        let kind = self.kind.to_possible_value().unwrap();
+        let mut options = vec![];
+        if let Some(count) = self.count {
+            options.push(format!("count={count}"));
+        }
+        if let Some(opacity) = self.opacity {
+            options.push(format!("opacity={opacity}"));
+        }
+        if let InitScaleRule::Constant(scale) = self.scale {
+            options.push(format!("scale={scale}"));
+        }
+
+        f.write_str(kind.get_name())?;
+        if !options.is_empty() {
+            write!(f, ":{}", options.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InitStrategy {
+    type Err = Report;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (kind, options) = value.split_once(':').unwrap_or((value, ""));
+        let mut strategy = Self {
+            kind: InitKind::from_str(kind.trim(), true).map_err(|e| eyre!(e))?,
+            ..Default::default()
+        };
+
+        for option in options.split(',').filter(|o| !o.trim().is_empty()) {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| eyre!("Invalid initialization option: {option:?}"))?;
+            let value = value.trim();
+            match key.trim() {
+                "count" => strategy.count = Some(value.parse()?),
+                "opacity" => strategy.opacity = Some(value.parse()?),
+                "scale" => {
+                    strategy.scale = match value {
+                        "neighbor" => InitScaleRule::Neighbor,
+                        value => InitScaleRule::Constant(value.parse()?),
+                    }
+                },
+                key => return Err(eyre!("Unknown initialization option: {key:?}")),
+            }
+        }
+
+        // NOTE: The inverse sigmoid of 0 or 1 is infinite.
+        if strategy.opacity.is_some_and(|o| !(o > 0.0 && o < 1.0)) {
+            return Err(eyre!("The initial opacity should be in (0, 1): {value:?}"));
+        }
+        if matches!(strategy.scale, InitScaleRule::Constant(s) if s <= 0.0) {
+            return Err(eyre!("The initial scaling should be positive: {value:?}"));
+        }
+
+        Ok(strategy)
+    }
+}
+
+impl From<InitStrategy> for String {
+    #[inline]
+    fn from(strategy: InitStrategy) -> Self {
+        strategy.to_string()
+    }
+}
+
+impl TryFrom<String> for InitStrategy {
+    type Error = Report;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_strategy_from_str() {
+        let target = InitStrategy::default();
+        let output = "sfm".parse::<InitStrategy>().unwrap();
+        assert_eq!(output, target);
+
+        let target = InitStrategy {
+            kind: InitKind::Box,
+            count: Some(50000),
+            opacity: Some(0.1),
+            scale: InitScaleRule::Constant(0.01),
+        };
+        let output = "box:count=50000,opacity=0.1,scale=0.01"
+            .parse::<InitStrategy>()
+            .unwrap();
+        assert_eq!(output, target);
+
+        let target = InitStrategy {
+            kind: InitKind::Sphere,
+            count: Some(1000),
+            ..Default::default()
+        };
+        let output = " Sphere : count = 1000 , scale = neighbor "
+            .parse::<InitStrategy>()
+            .unwrap();
+        assert_eq!(output, target);
+    }
+
+    #[test]
+    fn init_strategy_from_str_invalid() {
+        [
+            "",
+            "mesh",
+            "box:count",
+            "box:count=-1",
+            "box:size=1",
+            "box:opacity=1.5",
+            "box:opacity=0",
+            "box:opacity=1",
+            "box:opacity=NaN",
+            "box:scale=0",
+            "box:scale=far",
+        ]
+        .into_iter()
+        .for_each(|value| {
+            assert!(value.parse::<InitStrategy>().is_err(), "{value:?}");
+        });
+    }
+
+    #[test]
+    fn init_strategy_round_trip() {
+        [
+            "sfm",
+            "sfm:count=1000",
+            "box:count=50000,opacity=0.1,scale=0.01",
+            "sphere:opacity=0.5",
+        ]
+        .into_iter()
+        .for_each(|target| {
+            let strategy = target.parse::<InitStrategy>().unwrap();
+            assert_eq!(strategy.to_string(), target);
+
+            let value = serde_json::to_value(&strategy).unwrap();
+            assert_eq!(value, serde_json::Value::from(target));
+            let output = serde_json::from_value::<InitStrategy>(value).unwrap();
+            assert_eq!(output, strategy);
+        });
+    }
+
+    #[test]
+    fn train_arguments_deserialize_without_new_fields() {
+        let target =
+            TrainArguments::try_parse_from(["train", "-s", "data", "-m", "model"])
+                .unwrap();
+
+        let mut value = serde_json::to_value(&target).unwrap();
+        let arguments = value.as_object_mut().unwrap();
+        [
+            "init",
+            "feature_lr_final",
+            "position_lr_schedule",
+            "lr_warmup_steps",
+            "lr_milestones",
+            "lr_gamma",
+            "camera_sampling",
+            "resolution_factors",
+            "resolution_iterations",
+            "max_gaussians",
+            "max_duration",
+            "early_stopping_interval",
+            "early_stopping_patience",
+            "early_stopping_metric",
+            "seed",
+            "preset",
+        ]
+        .into_iter()
+        .for_each(|key| {
+            assert!(arguments.remove(key).is_some(), "{key:?}");
+        });
+        let arguments = arguments["common_arguments"].as_object_mut().unwrap();
+        [
+            "normalize",
+            "progress",
+            "progress_file",
+            "progress_interval",
+        ]
+        .into_iter()
+        .for_each(|key| {
+            assert!(arguments.remove(key).is_some(), "{key:?}");
+        });
+
+        let output = serde_json::from_value::<TrainArguments>(value).unwrap();
+        assert_eq!(output, target);
+    }
+}
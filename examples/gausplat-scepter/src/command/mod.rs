@@ -1,18 +1,27 @@
 //! Command module.
 
+pub mod dataset;
 pub mod gaussian_3d;
+pub mod preset;
+pub mod report;
 
 pub use super::*;
 pub use clap::{builder::styling, Command, FromArgMatches, Parser, ValueEnum};
 pub use color_eyre::Report;
+pub use dataset::DatasetCommand;
 pub use gaussian_3d::{Gaussian3dCommonArguments, Gaussian3dModelCommand};
+pub use report::ReportArguments;
 pub use serde::{Deserialize, Serialize};
 
-use clap::CommandFactory;
+use clap::{parser::ValueSource, ArgAction, ArgMatches, CommandFactory};
+use color_eyre::eyre::eyre;
 use gausplat::loader::source::file::{File, Opener};
+use serde_json::Value;
 use std::{
+    collections::HashSet,
     fs::create_dir_all,
-    io::BufReader,
+    io::{BufReader, Read},
+    iter,
     path::{Path, PathBuf},
 };
 
@@ -58,10 +67,20 @@ pub enum ModelCommand {
     #[serde(rename = "inherit")]
     Run {
         /// Arguments file path.
-        /// It is a JSON file containing the entire arguments.
+        /// It is a JSON, TOML or YAML file containing the arguments.
+        /// The missing arguments fall back to the default values.
         #[arg(verbatim_doc_comment, rename_all = "snake_case")]
         #[arg(index = 1, value_name = "Path")]
         path: PathBuf,
+
+        /// Overriding the argument as 'KEY=VALUE'.
+        /// The key is a dot-separated path relative to the task arguments,
+        /// e.g. 'iterations=7000' or 'common_arguments.model_path=out/x'.
+        /// The value is parsed as JSON if possible, otherwise as a string.
+        #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        #[serde(default)]
+        sets: Vec<String>,
     },
 
     /// Run tasks for 3DGS.
@@ -69,6 +88,36 @@ pub enum ModelCommand {
     #[command(subcommand, name = "3dgs")]
     #[serde(rename = "3dgs")]
     Gaussian3d(Box<Gaussian3dModelCommand>),
+
+    /// Run tasks for datasets.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[command(subcommand, name = "dataset")]
+    #[serde(rename = "dataset")]
+    Dataset(Box<DatasetCommand>),
+
+    /// Generate comparison tables from model directories.
+    #[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+    #[command(name = "report")]
+    #[serde(rename = "report")]
+    Report(Box<ReportArguments>),
+}
+
+/// Decode the JSON, TOML or YAML file by its extension.
+pub fn decode_value(file_path: impl AsRef<Path>) -> Result<Value, Report> {
+    let file_path = file_path.as_ref();
+    let mut text = String::new();
+    BufReader::new(File::open(file_path)?).read_to_string(&mut text)?;
+    let extension = file_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    Ok(match extension.as_str() {
+        "toml" => toml::from_str::<Value>(&text)?,
+        "yaml" | "yml" => serde_yaml::from_str::<Value>(&text)?,
+        _ => serde_json::from_str::<Value>(&text)?,
+    })
 }
 
 impl GausplatArguments {
@@ -78,20 +127,217 @@ impl GausplatArguments {
         <Self as CommandFactory>::command().styles(STYLES)
     }
 
-    /// Load the arguments from the file.
+    /// Load the arguments from the file with the overrides of `sets`.
+    ///
+    /// The file may be partial. It is converted to the command line,
+    /// so the missing arguments fall back to the default values.
+    pub fn load(
+        file_path: impl AsRef<Path>,
+        sets: &[String],
+    ) -> Result<Self, Report> {
+        let file_path = file_path.as_ref();
+
+        // Decoding the file
+
+        let mut value = decode_value(file_path)?;
+
+        // Finding the task arguments
+
+        let mut command = Self::command();
+        let mut names = vec![];
+        while let Some(name) = value
+            .pointer(&Self::get_pointer(&names))
+            .and_then(Value::as_object)
+            .and_then(|object| {
+                object
+                    .keys()
+                    .find(|key| command.find_subcommand(key).is_some())
+                    .cloned()
+            })
+        {
+            // NOTE: The subcommand is found.
+            command = command.find_subcommand(&name).unwrap().to_owned();
+            names.push(name);
+        }
+        if names.is_empty() {
+            return Err(eyre!("Unrecognizable task in the arguments: {file_path:?}"));
+        }
+        // NOTE: The pointer refers to an object.
+        let arguments = value.pointer_mut(&Self::get_pointer(&names)).unwrap();
+
+        // Overriding the arguments
+
+        for set in sets {
+            let (key, value_set) = set
+                .split_once('=')
+                .ok_or_else(|| eyre!("The override should be 'KEY=VALUE': {set:?}"))?;
+            let value_set = serde_json::from_str::<Value>(value_set)
+                .unwrap_or_else(|_| Value::String(value_set.to_owned()));
+            let target = key.split('.').try_fold(&mut *arguments, |target, key| {
+                if !target.is_object() {
+                    return Err(eyre!("The override key is not an object path: {key:?}"));
+                }
+                // NOTE: The target is an object.
+                let object = target.as_object_mut().unwrap();
+                Ok(object.entry(key).or_insert(Value::Null))
+            })?;
+            *target = value_set;
+        }
+
+        // Parsing the arguments as the command line
+
+        let mut args = vec![Self::command().get_name().to_owned()];
+        args.extend(names);
+        let mut args_positional = vec![];
+        Self::push_args(&command, arguments, &mut args, &mut args_positional)?;
+        if !args_positional.is_empty() {
+            args_positional.sort_by_key(|(index, _)| *index);
+            args.push("--".into());
+            args.extend(args_positional.into_iter().flat_map(|(_, values)| values));
+        }
+
+        Self::from_matches(&mut Self::command().try_get_matches_from(args)?)
+    }
+
+    /// Return the arguments from the `matches` with the preset applied.
+    ///
+    /// The explicit arguments in the `matches` override the preset.
+    pub fn from_matches(matches: &mut ArgMatches) -> Result<Self, Report> {
+        // Finding the explicit arguments of the task
+
+        let mut command = Self::command();
+        let mut names = vec![];
+        let mut matches_task = &*matches;
+        while let Some((name, matches_sub)) = matches_task.subcommand() {
+            // NOTE: The subcommand is matched.
+            command = command.find_subcommand(name).unwrap().to_owned();
+            names.push(name.to_owned());
+            matches_task = matches_sub;
+        }
+        let ids_explicit = matches_task
+            .ids()
+            .filter(|id| {
+                matches_task.value_source(id.as_str()) == Some(ValueSource::CommandLine)
+            })
+            .map(|id| id.to_string())
+            .collect::<HashSet<_>>();
+
+        let arguments = Self::from_arg_matches_mut(matches)?;
+
+        // Applying the preset
+
+        let mut value = serde_json::to_value(&arguments)?;
+        let Some(arguments_task) = value.pointer_mut(&Self::get_pointer(&names)) else {
+            return Ok(arguments);
+        };
+        let Some(name) = preset::find_value_mut(&command, arguments_task, "preset")
+            .and_then(|name| name.as_str())
+            .map(str::to_owned)
+        else {
+            return Ok(arguments);
+        };
+
+        for (key, value_preset) in preset::get_preset(&name)? {
+            if ids_explicit.contains(&key) {
+                continue;
+            }
+            let target = preset::find_value_mut(&command, arguments_task, &key)
+                .ok_or_else(|| {
+                    eyre!("Unknown argument in the preset {name:?}: {key:?}")
+                })?;
+            *target = value_preset;
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Push the command line arguments of the `command` from the `arguments`.
+    ///
+    /// The nested objects are flattened, and the null values are skipped.
+    /// The empty arrays are pushed as the flags without values if possible.
+    pub fn push_args(
+        command: &Command,
+        arguments: &Value,
+        args: &mut Vec<String>,
+        args_positional: &mut Vec<(usize, Vec<String>)>,
+    ) -> Result<(), Report> {
+        let object = arguments
+            .as_object()
+            .ok_or_else(|| eyre!("The arguments should be an object: {arguments}"))?;
+
+        for (key, value) in object {
+            let arg = command.get_arguments().find(|arg| arg.get_id() == key);
+            let values = match (arg, value) {
+                (None, Value::Object(_)) => {
+                    Self::push_args(command, value, args, args_positional)?;
+                    continue;
+                },
+                (None, _) => return Err(eyre!("Unknown argument: {key:?}")),
+                (Some(_), Value::Null) => continue,
+                (Some(_), Value::Array(values)) => {
+                    values.iter().map(Self::to_arg).collect()
+                },
+                (Some(_), value) => vec![Self::to_arg(value)],
+            };
+            // NOTE: The argument is found.
+            let arg = arg.unwrap();
+
+            match (arg.get_action(), arg.get_long()) {
+                (ArgAction::Count, Some(long)) => {
+                    let count = value.as_u64().unwrap_or_default() as usize;
+                    args.extend(iter::repeat(format!("--{long}")).take(count));
+                },
+                (ArgAction::SetTrue, Some(long)) => {
+                    if value.as_bool() == Some(true) {
+                        args.push(format!("--{long}"));
+                    }
+                },
+                (ArgAction::SetFalse, Some(long)) => {
+                    if value.as_bool() == Some(false) {
+                        args.push(format!("--{long}"));
+                    }
+                },
+                (_, Some(long))
+                    if values.is_empty()
+                        && arg
+                            .get_num_args()
+                            .is_some_and(|range| range.min_values() == 0) =>
+                {
+                    args.push(format!("--{long}"));
+                },
+                (_, Some(long)) => {
+                    args.extend(
+                        values.into_iter().map(|value| format!("--{long}={value}")),
+                    );
+                },
+                (_, None) => {
+                    args_positional.push((arg.get_index().unwrap_or_default(), values));
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the JSON pointer to the task arguments of the subcommand `names`.
     #[inline]
-    pub fn load(file_path: impl AsRef<Path>) -> Result<Self, Report> {
-        Ok(serde_json::from_reader(&mut BufReader::new(File::open(
-            file_path.as_ref(),
-        )?))?)
+    pub fn get_pointer(names: &[String]) -> String {
+        names.iter().map(|name| format!("/{name}")).collect()
+    }
+
+    /// Return the command line argument of the `value`.
+    #[inline]
+    pub fn to_arg(value: &Value) -> String {
+        match value {
+            Value::String(value) => value.to_owned(),
+            value => value.to_string(),
+        }
     }
 
     /// Parse the arguments from the command line.
     #[inline]
     pub fn parse() -> Result<Self, Report> {
-        Ok(Self::from_arg_matches_mut(
-            &mut Self::command().get_matches(),
-        )?)
+        Self::from_matches(&mut Self::command().get_matches())
     }
 
     /// Save the arguments to the file.
@@ -118,3 +364,82 @@ impl GausplatArguments {
         Ok(args_file_path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_with_empty_array() {
+        let directory = std::env::temp_dir()
+            .join(format!("gausplat-scepter-load-{}", std::process::id()));
+        create_dir_all(&directory).unwrap();
+
+        let target = GausplatArguments::command()
+            .try_get_matches_from([
+                "gausplat-scepter",
+                "3dgs",
+                "train",
+                "-s",
+                "data",
+                "-m",
+                "model",
+                "--test_iterations",
+                "--lr_milestones",
+            ])
+            .unwrap();
+        let target = GausplatArguments::from_arg_matches(&target).unwrap();
+        let file_path = target.save(&directory, "args-train").unwrap();
+
+        let output = GausplatArguments::load(&file_path, &[]).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(output, target);
+
+        let value = serde_json::to_value(&output).unwrap();
+        let arguments = value.pointer("/3dgs/train").unwrap();
+        assert_eq!(arguments["test_iterations"], serde_json::json!([]));
+        assert_eq!(
+            arguments["save_iterations"],
+            serde_json::json!([7000, 30000])
+        );
+    }
+
+    #[test]
+    fn from_matches_with_preset() {
+        let directory = std::env::temp_dir()
+            .join(format!("gausplat-scepter-preset-{}", std::process::id()));
+        create_dir_all(&directory).unwrap();
+        // NOTE: The user presets would override the built-in preset.
+        std::env::set_var("XDG_CONFIG_HOME", directory.join("config"));
+
+        let mut matches = GausplatArguments::command()
+            .try_get_matches_from([
+                "gausplat-scepter",
+                "3dgs",
+                "train",
+                "-s",
+                "data",
+                "-m",
+                "model",
+                "--preset",
+                "truck",
+                "--percent_dense",
+                "0.02",
+            ])
+            .unwrap();
+        let target = GausplatArguments::from_matches(&mut matches).unwrap();
+        let file_path = target.save(&directory, "args-train").unwrap();
+        let value = decode_value(&file_path).unwrap();
+        let output = GausplatArguments::load(&file_path, &[]).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let arguments = value.pointer("/3dgs/train").unwrap();
+        assert_eq!(arguments["preset"], serde_json::json!("truck"));
+        assert_eq!(arguments["percent_dense"], serde_json::json!(0.02));
+        assert_eq!(
+            arguments["densify_grad_threshold"],
+            serde_json::json!(0.00035)
+        );
+        assert_eq!(output, target);
+    }
+}
@@ -0,0 +1,184 @@
+//! Presets of the arguments.
+
+pub use super::*;
+
+use color_eyre::eyre::eyre;
+use serde_json::{Map, Value};
+use std::env;
+
+/// Built-in presets of the training arguments for the benchmark scenes.
+///
+/// They are the extra configurations in `doc/REPORT.md`.
+pub const PRESETS_BUILTIN: &[(&str, &str)] = &[
+    ("playroom", "percent_dense = 0.017"),
+    ("train", "percent_dense = 0.015"),
+    (
+        "truck",
+        "densify_grad_threshold = 0.00035\npercent_dense = 0.012",
+    ),
+];
+
+/// Extensions of the user preset files in the order of precedence.
+pub const PRESET_FILE_EXTENSIONS: &[&str] = &["toml", "yaml", "yml", "json"];
+
+/// Return the directory path of the user preset files.
+///
+/// It is `$XDG_CONFIG_HOME/gausplat/presets` or `~/.config/gausplat/presets`.
+pub fn get_presets_directory() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|directory| directory.join("gausplat").join("presets"))
+}
+
+/// Return the arguments of the preset `name`.
+///
+/// The user preset file overrides the built-in preset of the same name.
+/// The nested objects are flattened.
+///
+/// The `name` should only contain ASCII alphanumerics, '-' and '_'.
+pub fn get_preset(name: &str) -> Result<Map<String, Value>, Report> {
+    if !is_preset_name_valid(name) {
+        return Err(eyre!(
+            "The preset name should only contain ASCII alphanumerics, '-' and '_': \
+            {name:?}"
+        ));
+    }
+
+    let file_path = get_presets_directory().and_then(|directory| {
+        PRESET_FILE_EXTENSIONS
+            .iter()
+            .map(|extension| directory.join(format!("{name}.{extension}")))
+            .find(|file_path| file_path.is_file())
+    });
+    let preset = match file_path {
+        Some(file_path) => {
+            log::info!(target: "gausplat::scepter::preset", "load {file_path:?}");
+            decode_value(file_path)?
+        },
+        None => {
+            let (_, preset) = PRESETS_BUILTIN
+                .iter()
+                .find(|(name_builtin, _)| *name_builtin == name)
+                .ok_or_else(|| {
+                    let names = PRESETS_BUILTIN
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>();
+                    eyre!(
+                        "Unknown preset: {name:?}. \
+                        The built-in presets are {names:?}, \
+                        and the user presets are in {:?}",
+                        get_presets_directory().unwrap_or_default(),
+                    )
+                })?;
+            toml::from_str::<Value>(preset)?
+        },
+    };
+
+    let mut arguments = Map::new();
+    flatten_value(preset, &mut arguments)?;
+    Ok(arguments)
+}
+
+/// Return `true` if the preset `name` is not empty and can not escape the directory.
+#[inline]
+pub fn is_preset_name_valid(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Flatten the nested objects of the `value` into the `arguments`.
+pub fn flatten_value(
+    value: Value,
+    arguments: &mut Map<String, Value>,
+) -> Result<(), Report> {
+    let Value::Object(object) = value else {
+        return Err(eyre!("The preset should be an object: {value}"));
+    };
+    for (key, value) in object {
+        match value {
+            Value::Object(_) => flatten_value(value, arguments)?,
+            value => {
+                arguments.insert(key, value);
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Return the value of the argument `id` of the `command` in the nested `value`.
+///
+/// Only the objects of the flattened arguments are searched,
+/// so the values of other arguments are never matched.
+pub fn find_value_mut<'v>(
+    command: &Command,
+    value: &'v mut Value,
+    id: &str,
+) -> Option<&'v mut Value> {
+    let is_argument = |key: &str| command.get_arguments().any(|arg| arg.get_id() == key);
+    if !is_argument(id) {
+        return None;
+    }
+
+    let object = value.as_object_mut()?;
+    if object.contains_key(id) {
+        return object.get_mut(id);
+    }
+    object
+        .iter_mut()
+        .filter(|(key, _)| !is_argument(key))
+        .find_map(|(_, value)| find_value_mut(command, value, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_preset_with_invalid_name() {
+        [
+            "",
+            ".",
+            "..",
+            "../truck",
+            "truck/..",
+            "/truck",
+            "presets\\truck",
+            "truck.toml",
+        ]
+        .into_iter()
+        .for_each(|name| {
+            assert!(!is_preset_name_valid(name), "{name:?}");
+            assert!(get_preset(name).is_err(), "{name:?}");
+        });
+
+        ["truck", "my-scene_2"].into_iter().for_each(|name| {
+            assert!(is_preset_name_valid(name), "{name:?}");
+        });
+    }
+
+    #[test]
+    fn find_value_mut_with_exact_id() {
+        let command = GausplatArguments::command()
+            .find_subcommand("3dgs")
+            .and_then(|command| command.find_subcommand("train"))
+            .unwrap()
+            .to_owned();
+        let mut value = serde_json::json!({
+            "camera_sampling": {"quiet": 9},
+            "common_arguments": {"quiet": 1, "model_path": "model"},
+            "preset": "truck",
+        });
+
+        let target = find_value_mut(&command, &mut value, "quiet").unwrap();
+        assert_eq!(*target, serde_json::json!(1));
+        let target = find_value_mut(&command, &mut value, "preset").unwrap();
+        assert_eq!(*target, serde_json::json!("truck"));
+        assert!(find_value_mut(&command, &mut value, "common_arguments").is_none());
+        assert!(find_value_mut(&command, &mut value, "unknown").is_none());
+    }
+}
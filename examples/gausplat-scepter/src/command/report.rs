@@ -0,0 +1,29 @@
+//! Command for reports.
+
+pub use super::*;
+
+/// Generate comparison tables from model directories.
+#[derive(Clone, Debug, Deserialize, Parser, PartialEq, Serialize)]
+#[command(verbatim_doc_comment, rename_all = "snake_case", after_help = AFTER_HELP)]
+#[command(next_line_help = true)]
+pub struct ReportArguments {
+    /// Directory paths of the model trees.
+    /// A model directory includes the sub-directory of 'point_cloud'.
+    /// The method is labeled as 'LABEL=PATH' if specified.
+    /// [default label: 'Gausplat-3DGS' or 'Original-3DGS']
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(index = 1, value_name = "Path", num_args = 1.., required = true)]
+    pub tree_paths: Vec<String>,
+
+    /// Output directory path.
+    /// The files of 'report.md' and 'report.csv' are written.
+    /// [default: Printing the Markdown tables]
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, short, value_name = "Path")]
+    pub output_path: Option<PathBuf>,
+
+    /// Number of decimal places for the quality metrics.
+    #[arg(verbatim_doc_comment, rename_all = "snake_case")]
+    #[arg(long, value_name = "USize", default_value_t = 3)]
+    pub precision: usize,
+}
@@ -1,6 +1,12 @@
 pub use gausplat_scepter::{
-    command::{GausplatArguments, Gaussian3dModelCommand, ModelCommand, Report},
-    runner::Runner,
+    command::{
+        DatasetCommand, GausplatArguments, Gaussian3dModelCommand, ModelCommand, Report,
+    },
+    runner::{
+        dataset::check::{Invalid, EXIT_CODE_INVALID},
+        progress::write_error,
+        Interrupted, Runner, EXIT_CODE_INTERRUPTED,
+    },
 };
 use std::time::Instant;
 
@@ -12,7 +18,7 @@ pub fn main() -> Result<(), Report> {
 
     let args = GausplatArguments::parse()?;
     let args = match &args.model {
-        Run { path } => GausplatArguments::load(path)?,
+        Run { path, sets } => GausplatArguments::load(path, sets)?,
         _ => args,
     };
     log::debug!(target: "gausplat::scepter::main", "args > {args:#?}");
@@ -29,21 +35,66 @@ pub fn main() -> Result<(), Report> {
         Gaussian3d(command) => {
             use Gaussian3dModelCommand::*;
             match command.as_ref() {
-                Train(args_train) => {
-                    args.save(&args_train.common_arguments.model_path, "args-train")?;
-                    let runner = args_train.init()?;
+                Bench(args_bench) => {
+                    let runner = args_bench.init()?;
                     log_runner(&runner);
                     runner.run()?;
                 },
+                Train(args_train) => {
+                    let args_common = &args_train.common_arguments;
+                    let runner = args
+                        .save(&args_common.model_path, "args-train")
+                        .and_then(|_| args_train.init())
+                        .or_else(|report| write_error(args_common, report))?;
+                    log_runner(&runner);
+                    runner.run().or_else(exit_if_interrupted)?;
+                },
                 Render(args_render) => {
-                    args.save(&args_render.common_arguments.model_path, "args-render")?;
-                    let runner = args_render.init()?;
+                    let args_common = &args_render.common_arguments;
+                    let runner = args
+                        .save(&args_common.model_path, "args-render")
+                        .and_then(|_| args_render.init())
+                        .or_else(|report| write_error(args_common, report))?;
                     log_runner(&runner);
                     runner.run()?;
                 },
+                Pipeline(args_pipeline) => {
+                    let args_common = &args_pipeline.train_arguments.common_arguments;
+                    let runner = args
+                        .save(&args_common.model_path, "args-pipeline")
+                        .and_then(|_| args_pipeline.init())
+                        .or_else(|report| write_error(args_common, report))?;
+                    log_runner(&runner);
+                    runner.run().or_else(exit_if_interrupted)?;
+                },
                 _ => unimplemented!(),
             }
         },
+        Dataset(command) => {
+            use DatasetCommand::*;
+            match command.as_ref() {
+                Check(args_check) => {
+                    let runner = args_check.init()?;
+                    log_runner(&runner);
+                    runner.run().or_else(exit_if_invalid)?;
+                },
+                Export(args_export) => {
+                    let runner = args_export.init()?;
+                    log_runner(&runner);
+                    runner.run()?;
+                },
+                Info(args_info) => {
+                    let runner = args_info.init()?;
+                    log_runner(&runner);
+                    runner.run()?;
+                },
+            }
+        },
+        Report(args_report) => {
+            let runner = args_report.init()?;
+            log_runner(&runner);
+            runner.run()?;
+        },
         Run { .. } => unreachable!(),
     };
     log::info!(target: "gausplat::scepter::main", "run in {:.03?}", time.elapsed());
@@ -51,6 +102,25 @@ pub fn main() -> Result<(), Report> {
     Ok(())
 }
 
+/// Exit with [`EXIT_CODE_INTERRUPTED`] if the `report` is an interruption.
+pub fn exit_if_interrupted(report: Report) -> Result<(), Report> {
+    if let Some(interrupted) = report.downcast_ref::<Interrupted>() {
+        log::warn!(target: "gausplat::scepter::main", "{interrupted}");
+        std::process::exit(EXIT_CODE_INTERRUPTED);
+    }
+    Err(report)
+}
+
+/// Exit with [`EXIT_CODE_INVALID`] if the `report` is an invalid dataset.
+pub fn exit_if_invalid(report: Report) -> Result<(), Report> {
+    // NOTE: The issues are already reported.
+    if let Some(invalid) = report.downcast_ref::<Invalid>() {
+        log::warn!(target: "gausplat::scepter::main", "{invalid}");
+        std::process::exit(EXIT_CODE_INVALID);
+    }
+    Err(report)
+}
+
 pub fn init() -> Result<(), Report> {
     color_eyre::install()?;
     std::env::set_var("RUST_BACKTRACE", "full");
@@ -0,0 +1,365 @@
+//! Checking runner for datasets.
+
+pub use super::*;
+pub use command::dataset::CheckArguments;
+
+use color_eyre::eyre::eyre;
+use gausplat::loader::source::colmap::{Cameras, Images, Points};
+use nerfstudio::{Transforms, TRANSFORMS_FILE_NAME};
+use std::{
+    collections::{BTreeMap, HashSet},
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Exit code when the dataset has errors.
+pub const EXIT_CODE_INVALID: i32 = 1;
+
+/// Checking runner.
+#[derive(Clone, Debug)]
+pub struct CheckRunner {
+    /// Arguments for checking.
+    pub arguments: CheckArguments,
+}
+
+/// The dataset has errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Invalid {
+    /// Number of the errors.
+    pub error_count: usize,
+}
+
+/// Issue found in the dataset.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Issue {
+    /// The dataset cannot be used as is.
+    Error(String),
+    /// The dataset can be used, but it may be unexpected.
+    Warning(String),
+}
+
+impl CheckArguments {
+    /// Initialize the checking runner.
+    pub fn init(&self) -> Result<CheckRunner, Report> {
+        let arguments = self.to_owned();
+
+        let source_path = &self.common_arguments.source_path;
+        if !source_path.is_dir() {
+            return Err(eyre!("The dataset directory is not found: {source_path:?}"));
+        }
+
+        Ok(CheckRunner { arguments })
+    }
+}
+
+impl CheckRunner {
+    /// Return the issues found in the dataset.
+    pub fn check(&self) -> Vec<Issue> {
+        use command::dataset::SourceType::*;
+
+        let (sparse_model_directory, _) =
+            get_colmap_directories(&self.arguments.common_arguments);
+        let images_directory = get_images_directory(&self.arguments.common_arguments);
+        let mut issues = vec![];
+
+        // Decoding the files
+
+        let (cameras, images, points) = match self.arguments.common_arguments.source_type
+        {
+            Colmap => (
+                Self::decode_or_report::<Cameras>(
+                    sparse_model_directory.join("cameras.bin"),
+                    &mut issues,
+                ),
+                Self::decode_or_report::<Images>(
+                    sparse_model_directory.join("images.bin"),
+                    &mut issues,
+                ),
+                Self::decode_or_report::<Points>(
+                    sparse_model_directory.join("points3D.bin"),
+                    &mut issues,
+                ),
+            ),
+            Nerfstudio => {
+                let source_path = &self.arguments.common_arguments.source_path;
+                match Transforms::read(source_path)
+                    .and_then(|transforms| transforms.into_colmap(source_path))
+                {
+                    Ok((cameras, images, points)) => {
+                        (Some(cameras), Some(images), Some(points))
+                    },
+                    Err(err) => {
+                        issues.push(Issue::Error(format!(
+                            "{:?} cannot be decoded: {err}",
+                            source_path.join(TRANSFORMS_FILE_NAME),
+                        )));
+                        (None, None, None)
+                    },
+                }
+            },
+        };
+
+        self.check_colmap(cameras, images, points, &images_directory, issues)
+    }
+
+    /// Return the issues found in the COLMAP data.
+    pub fn check_colmap(
+        &self,
+        cameras: Option<Cameras>,
+        images: Option<Images>,
+        points: Option<Points>,
+        images_directory: &Path,
+        mut issues: Vec<Issue>,
+    ) -> Vec<Issue> {
+        // Checking the images
+
+        if let Some(images) = &images {
+            let mut name_counts = BTreeMap::<&str, usize>::new();
+            images.values().for_each(|image| {
+                *name_counts.entry(image.file_name.as_str()).or_default() += 1;
+            });
+            name_counts
+                .iter()
+                .filter(|(_, count)| **count > 1)
+                .for_each(|(name, count)| {
+                    issues.push(Issue::Error(format!(
+                        "{name:?} is referenced by {count} image entries"
+                    )));
+                });
+
+            for (image_id, image) in images {
+                let is_pose_finite = image
+                    .quaternion
+                    .iter()
+                    .chain(image.translation.iter())
+                    .all(|value| value.is_finite());
+                if !is_pose_finite {
+                    issues.push(Issue::Error(format!(
+                        "Image {image_id} ({:?}) has a non-finite pose",
+                        image.file_name,
+                    )));
+                }
+
+                let camera = cameras.as_ref().and_then(|cameras| {
+                    let camera = cameras.get(&image.camera_id);
+                    if camera.is_none() {
+                        issues.push(Issue::Error(format!(
+                            "Camera {} of image {image_id} ({:?}) has no intrinsics",
+                            image.camera_id, image.file_name,
+                        )));
+                    }
+                    camera
+                });
+
+                let image_file_path = images_directory.join(&image.file_name);
+                if !image_file_path.is_file() {
+                    issues.push(Issue::Error(format!(
+                        "Image {image_id} has no file: {image_file_path:?}"
+                    )));
+                    continue;
+                }
+
+                let (width, height) = match image::image_dimensions(&image_file_path) {
+                    Ok(size) => (size.0 as u64, size.1 as u64),
+                    Err(err) => {
+                        issues.push(Issue::Error(format!(
+                            "{image_file_path:?} cannot be decoded: {err}"
+                        )));
+                        continue;
+                    },
+                };
+                if let Some(camera) = camera {
+                    let (width_camera, height_camera) = (camera.width(), camera.height());
+                    if (width, height) == (width_camera, height_camera) {
+                        continue;
+                    }
+                    // NOTE: The downscaled images keep the aspect ratio.
+                    let ratio_error =
+                        (width * height_camera).abs_diff(height * width_camera) as f64
+                            / (height * height_camera).max(1) as f64;
+                    let issue = format!(
+                        "{image_file_path:?} has a size of {width}x{height}, \
+                        but camera {} has a size of {width_camera}x{height_camera}",
+                        image.camera_id,
+                    );
+                    issues.push(if ratio_error > 1e-2 {
+                        Issue::Error(issue)
+                    } else {
+                        Issue::Warning(issue)
+                    });
+                }
+            }
+
+            // Listing the unreferenced files
+
+            // NOTE: The images directory of Nerfstudio is the dataset directory,
+            // so only the sub-directories with referenced files are listed.
+            let is_nerfstudio = matches!(
+                self.arguments.common_arguments.source_type,
+                command::dataset::SourceType::Nerfstudio
+            );
+            let directories = name_counts
+                .keys()
+                .map(|name| name.rsplit_once('/').map_or("", |(directory, _)| directory))
+                .collect::<HashSet<_>>();
+            let names = name_counts.into_keys().collect::<HashSet<_>>();
+            match get_file_names(images_directory) {
+                Ok(mut names_unreferenced) => {
+                    names_unreferenced.retain(|name| !names.contains(name.as_str()));
+                    if is_nerfstudio {
+                        names_unreferenced.retain(|name| {
+                            let directory = name
+                                .rsplit_once('/')
+                                .map_or("", |(directory, _)| directory);
+                            directories.contains(directory)
+                        });
+                    }
+                    names_unreferenced.sort_unstable();
+                    names_unreferenced.into_iter().for_each(|name| {
+                        issues.push(Issue::Warning(format!(
+                            "{name:?} is not referenced by any image entry"
+                        )));
+                    });
+                },
+                Err(err) => issues.push(Issue::Error(format!(
+                    "{images_directory:?} cannot be read: {err}"
+                ))),
+            }
+        }
+
+        // Checking the points
+
+        if let Some(points) = &points {
+            let mut indices_non_finite = points
+                .iter()
+                .enumerate()
+                .filter(|(_, point)| !point.position.iter().all(|v| v.is_finite()))
+                .map(|(index, _)| index);
+            if let Some(index) = indices_non_finite.next() {
+                issues.push(Issue::Error(format!(
+                    "{} points have non-finite positions, e.g. point #{index}",
+                    indices_non_finite.count() + 1,
+                )));
+            }
+        }
+
+        issues
+    }
+}
+
+impl CheckRunner {
+    /// Decode the data from the file, or report the issue if it fails.
+    fn decode_or_report<D: Decoder>(
+        file_path: PathBuf,
+        issues: &mut Vec<Issue>,
+    ) -> Option<D> {
+        decode_file(&file_path)
+            .map_err(|err| {
+                issues.push(Issue::Error(format!(
+                    "{file_path:?} cannot be decoded: {err}"
+                )))
+            })
+            .ok()
+    }
+}
+
+impl Runner for CheckRunner {
+    fn run(self) -> Result<(), Report> {
+        let source_path = &self.arguments.common_arguments.source_path;
+        let issues = self.check();
+        let error_count = issues
+            .iter()
+            .filter(|issue| matches!(issue, Issue::Error(_)))
+            .count();
+        let warning_count = issues.len() - error_count;
+
+        issues.iter().for_each(|issue| println!("{issue}"));
+        println!(
+            "| Checking dataset | {source_path:?} | \
+            {error_count} error(s) | {warning_count} warning(s) |"
+        );
+
+        if error_count != 0 {
+            return Err(Invalid { error_count }.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the relative paths of the files in the `directory` and its sub-directories.
+///
+/// The paths are separated by '/' like the image file names in COLMAP.
+/// The symbolic links to directories are not followed.
+pub fn get_file_names(directory: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let directory = directory.as_ref();
+    let mut names = vec![];
+    let mut directories = vec![directory.to_owned()];
+    while let Some(directory_current) = directories.pop() {
+        for entry in fs::read_dir(directory_current)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                directories.push(path);
+            } else if path.is_file() {
+                // NOTE: The path is in the directory.
+                let name = path
+                    .strip_prefix(directory)
+                    .unwrap()
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                names.push(name);
+            }
+        }
+    }
+    Ok(names)
+}
+
+impl fmt::Display for Issue {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Error(message) => write!(f, "error: {message}"),
+            Self::Warning(message) => write!(f, "warning: {message}"),
+        }
+    }
+}
+
+impl fmt::Display for Invalid {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "The dataset has {} error(s)", self.error_count)
+    }
+}
+
+impl Error for Invalid {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_file_names_in_sub_directories() {
+        let directory = std::env::temp_dir()
+            .join(format!("gausplat-scepter-check-{}", std::process::id()));
+        fs::create_dir_all(directory.join("images_2").join("cam0")).unwrap();
+        fs::write(directory.join("0.jpg"), []).unwrap();
+        fs::write(directory.join("images_2").join("cam0").join("1.jpg"), []).unwrap();
+
+        let mut output = get_file_names(&directory).unwrap();
+        output.sort_unstable();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let target = vec!["0.jpg".to_owned(), "images_2/cam0/1.jpg".to_owned()];
+        assert_eq!(output, target);
+    }
+}
@@ -0,0 +1,135 @@
+//! Exporting runner for datasets.
+
+pub use super::*;
+pub use command::dataset::ExportArguments;
+
+use color_eyre::eyre::eyre;
+use gausplat::loader::source::colmap::{Cameras, Images, Points};
+use nerfstudio::{write_points, Transforms, POINTS_FILE_NAME};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// Exporting runner.
+#[derive(Clone)]
+pub struct ExportRunner {
+    /// Arguments for exporting.
+    pub arguments: ExportArguments,
+    /// Camera intrinsics.
+    pub cameras: Cameras,
+    /// Image entries.
+    pub images: Images,
+    /// Initial points.
+    pub points: Points,
+}
+
+impl ExportArguments {
+    /// Initialize the exporting runner.
+    pub fn init(&self) -> Result<ExportRunner, Report> {
+        use command::dataset::SourceType::*;
+
+        let arguments = self.to_owned();
+
+        let (cameras, images, points) = match self.common_arguments.source_type {
+            Colmap => {
+                let (sparse_model_directory, _) =
+                    get_colmap_directories(&self.common_arguments);
+                (
+                    decode_file(sparse_model_directory.join("cameras.bin"))?,
+                    decode_file(sparse_model_directory.join("images.bin"))?,
+                    decode_file(sparse_model_directory.join("points3D.bin"))?,
+                )
+            },
+            Nerfstudio => {
+                let source_path = &self.common_arguments.source_path;
+                Transforms::read(source_path)?.into_colmap(source_path)?
+            },
+        };
+
+        Ok(ExportRunner {
+            arguments,
+            cameras,
+            images,
+            points,
+        })
+    }
+}
+
+impl ExportRunner {
+    /// Copy the referenced image files to the `directory`.
+    pub fn copy_images(
+        &self,
+        directory: impl AsRef<Path>,
+    ) -> Result<(), Report> {
+        let images_directory = get_images_directory(&self.arguments.common_arguments);
+        let directory = directory.as_ref();
+
+        self.images.values().try_for_each(|image| {
+            let source = images_directory.join(&image.file_name);
+            let target = directory.join(&image.file_name);
+            // NOTE: The file name may include sub-directories.
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &target)
+                .map_err(|err| eyre!("{source:?} cannot be copied: {err}"))?;
+            Ok(())
+        })
+    }
+}
+
+impl Runner for ExportRunner {
+    fn run(self) -> Result<(), Report> {
+        use command::dataset::SourceType::*;
+
+        let output_path = self.arguments.output_path.as_path();
+        let images = self.arguments.common_arguments.images.as_path();
+        fs::create_dir_all(output_path)?;
+
+        match self.arguments.format {
+            Colmap => {
+                let sparse_model_directory =
+                    [output_path, "sparse".as_ref(), "0".as_ref()]
+                        .iter()
+                        .collect::<PathBuf>();
+                fs::create_dir_all(&sparse_model_directory)?;
+                encode_file(sparse_model_directory.join("cameras.bin"), &self.cameras)?;
+                encode_file(sparse_model_directory.join("images.bin"), &self.images)?;
+                encode_file(sparse_model_directory.join("points3D.bin"), &self.points)?;
+            },
+            Nerfstudio => {
+                let mut transforms =
+                    Transforms::from_colmap(&self.cameras, &self.images, images)?;
+                if !self.points.is_empty() {
+                    write_points(output_path.join(POINTS_FILE_NAME), &self.points)?;
+                    transforms.ply_file_path = Some(POINTS_FILE_NAME.into());
+                }
+                transforms.write(output_path)?;
+            },
+        }
+
+        self.copy_images(output_path.join(images))?;
+        log::info!(
+            target: "gausplat::scepter::dataset::export",
+            "export {} images to {output_path:?}", self.images.len(),
+        );
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ExportRunner {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("ExportRunner")
+            .field("arguments", &self.arguments)
+            .field("cameras.len()", &self.cameras.len())
+            .field("images.len()", &self.images.len())
+            .field("points.len()", &self.points.len())
+            .finish()
+    }
+}
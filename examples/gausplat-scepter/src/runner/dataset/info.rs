@@ -0,0 +1,394 @@
+//! Inspection runner for datasets.
+
+pub use super::*;
+pub use command::dataset::InfoArguments;
+
+use gausplat::loader::source::colmap::{Camera, Cameras, Images};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+/// Inspection runner.
+#[derive(Clone)]
+pub struct InfoRunner {
+    /// Arguments for inspection.
+    pub arguments: InfoArguments,
+    /// Camera intrinsics.
+    pub cameras: Cameras,
+    /// Image entries.
+    pub images: Images,
+    /// Reprojection errors of the points.
+    pub point_errors: Vec<f64>,
+}
+
+/// Summary of the dataset.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DatasetInfo {
+    /// Dataset directory path.
+    pub source_path: PathBuf,
+    /// Number of camera intrinsics.
+    pub camera_count: usize,
+    /// Number of cameras for each model.
+    pub camera_models: BTreeMap<String, usize>,
+    /// Number of image entries.
+    pub image_count: usize,
+    /// Number of image files for each size.
+    pub image_sizes: BTreeMap<String, usize>,
+    /// Horizontal focal lengths of the images.
+    pub focal_lengths_x: Option<Statistics>,
+    /// Vertical focal lengths of the images.
+    pub focal_lengths_y: Option<Statistics>,
+    /// Minimum and maximum of the camera centers.
+    pub bounding_box: [[f64; 3]; 2],
+    /// Mean of the camera centers.
+    pub center: [f64; 3],
+    /// Radius enclosing the camera centers.
+    pub radius: f64,
+    /// Number of points.
+    pub point_count: usize,
+    /// Reprojection errors of the points in pixels.
+    ///
+    /// It is `None` if the errors are unknown.
+    pub point_errors: Option<Statistics>,
+    /// Number of training images.
+    pub train_count: usize,
+    /// Names of testing images.
+    pub test_names: Vec<String>,
+}
+
+/// Statistics of the values.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Statistics {
+    /// Minimum value.
+    pub min: f64,
+    /// Mean value.
+    pub mean: f64,
+    /// Median value.
+    pub median: f64,
+    /// Maximum value.
+    pub max: f64,
+}
+
+impl InfoArguments {
+    /// Initialize the inspection runner.
+    pub fn init(&self) -> Result<InfoRunner, Report> {
+        use command::dataset::SourceType::*;
+
+        let arguments = self.to_owned();
+
+        let (cameras, images, point_errors) = match self.common_arguments.source_type {
+            Colmap => {
+                let (sparse_model_directory, _) =
+                    get_colmap_directories(&self.common_arguments);
+                let cameras = decode_file(sparse_model_directory.join("cameras.bin"))?;
+                let images = decode_file(sparse_model_directory.join("images.bin"))?;
+                let point_errors = InfoRunner::read_colmap_point_errors(
+                    sparse_model_directory.join("points3D.bin"),
+                )?;
+                (cameras, images, point_errors)
+            },
+            Nerfstudio => {
+                let source_path = &self.common_arguments.source_path;
+                let (cameras, images, points) =
+                    nerfstudio::Transforms::read(source_path)?
+                        .into_colmap(source_path)?;
+                // NOTE: The errors are unknown.
+                (cameras, images, vec![f64::NAN; points.len()])
+            },
+        };
+
+        Ok(InfoRunner {
+            arguments,
+            cameras,
+            images,
+            point_errors,
+        })
+    }
+}
+
+impl InfoRunner {
+    /// Return the summary of the dataset.
+    pub fn info(&self) -> DatasetInfo {
+        let images_directory = get_images_directory(&self.arguments.common_arguments);
+
+        // Counting the cameras and images
+
+        let mut camera_models = BTreeMap::<String, usize>::new();
+        self.cameras.values().for_each(|camera| {
+            *camera_models
+                .entry(get_camera_model_name(camera).to_owned())
+                .or_default() += 1;
+        });
+
+        let mut image_sizes = BTreeMap::<String, usize>::new();
+        self.images.values().for_each(|image| {
+            let size = image::image_dimensions(images_directory.join(&image.file_name))
+                .map(|(width, height)| format!("{width}x{height}"))
+                .unwrap_or_else(|_| "unknown".into());
+            *image_sizes.entry(size).or_default() += 1;
+        });
+
+        // Summarizing the intrinsics
+
+        let cameras = self
+            .images
+            .values()
+            .filter_map(|image| self.cameras.get(&image.camera_id))
+            .collect::<Vec<_>>();
+        let focal_lengths_x =
+            Statistics::new(cameras.iter().map(|c| c.focal_length_x()).collect());
+        let focal_lengths_y =
+            Statistics::new(cameras.iter().map(|c| c.focal_length_y()).collect());
+
+        // Bounding the camera centers
+
+        let centers = self
+            .images
+            .values()
+            .map(|image| Self::get_camera_center(image.quaternion, image.translation))
+            .collect::<Vec<_>>();
+        let bounding_box = centers.iter().fold(
+            [[f64::INFINITY; 3], [f64::NEG_INFINITY; 3]],
+            |[mut min, mut max], center| {
+                (0..3).for_each(|i| {
+                    min[i] = min[i].min(center[i]);
+                    max[i] = max[i].max(center[i]);
+                });
+                [min, max]
+            },
+        );
+        let count = centers.len().max(1) as f64;
+        let center = [0, 1, 2].map(|i| centers.iter().map(|c| c[i]).sum::<f64>() / count);
+        // NOTE: The radius is slightly enlarged as the scene radius for training.
+        let radius = centers
+            .iter()
+            .map(|c| {
+                (0..3)
+                    .map(|i| (c[i] - center[i]).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .fold(0.0, f64::max)
+            * 1.1;
+
+        // Splitting the images as the training runner does
+
+        let mut names = self
+            .images
+            .values()
+            .map(|image| image.file_name.to_owned())
+            .collect::<Vec<_>>();
+        names.sort_by(|a, b| Path::new(a).file_stem().cmp(&Path::new(b).file_stem()));
+        let test_names = names
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, name)| {
+                (self.arguments.eval && index % 8 == 0).then_some(name)
+            })
+            .collect::<Vec<_>>();
+
+        DatasetInfo {
+            source_path: self.arguments.common_arguments.source_path.to_owned(),
+            camera_count: self.cameras.len(),
+            camera_models,
+            image_count: self.images.len(),
+            image_sizes,
+            focal_lengths_x,
+            focal_lengths_y,
+            bounding_box,
+            center,
+            radius,
+            point_count: self.point_errors.len(),
+            point_errors: Statistics::new(self.point_errors.to_owned()),
+            train_count: self.images.len() - test_names.len(),
+            test_names,
+        }
+    }
+
+    /// Return the camera center in world space.
+    ///
+    /// `quaternion` is in the order of `[w, x, y, z]`.
+    pub fn get_camera_center(
+        quaternion: [f64; 4],
+        translation: [f64; 3],
+    ) -> [f64; 3] {
+        let rotation = get_rotation_from_quaternion(quaternion);
+
+        // C = -R^T * T
+        [0, 1, 2].map(|i| -(0..3).map(|j| rotation[j][i] * translation[j]).sum::<f64>())
+    }
+
+    /// Read the reprojection errors of the points from `points3D.bin`.
+    ///
+    /// The file is read directly since only the positions and colors are decoded
+    /// by [`colmap::Points`](gausplat::loader::source::colmap::Points).
+    pub fn read_colmap_point_errors(
+        file_path: impl AsRef<Path>
+    ) -> Result<Vec<f64>, Report> {
+        let mut reader = BufReader::new(File::open(file_path)?);
+        let read_u64 = |reader: &mut BufReader<File>| -> Result<u64, Report> {
+            let mut bytes = [0; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        };
+
+        // NOTE: The point count is not trusted for preallocation.
+        let point_count = read_u64(&mut reader)?;
+        let mut point_errors = vec![];
+        for _ in 0..point_count {
+            // NOTE: The point ID, position, and color are skipped.
+            reader.seek_relative(8 + 8 * 3 + 3)?;
+            point_errors.push(f64::from_bits(read_u64(&mut reader)?));
+            let track_count = read_u64(&mut reader)? as i64;
+            reader.seek_relative(track_count * 8)?;
+        }
+
+        Ok(point_errors)
+    }
+}
+
+impl Runner for InfoRunner {
+    fn run(self) -> Result<(), Report> {
+        let info = self.info();
+
+        if self.arguments.json {
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        } else {
+            println!("{info}");
+        }
+
+        Ok(())
+    }
+}
+
+impl Statistics {
+    /// Summarize the finite values.
+    ///
+    /// It returns `None` if there are no finite values.
+    pub fn new(mut values: Vec<f64>) -> Option<Self> {
+        values.retain(|value| value.is_finite());
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable_by(f64::total_cmp);
+
+        let count = values.len();
+        Some(Self {
+            min: values[0],
+            mean: values.iter().sum::<f64>() / count as f64,
+            median: values[count / 2],
+            max: values[count - 1],
+        })
+    }
+}
+
+/// Return the COLMAP model name of the `camera`.
+#[inline]
+pub fn get_camera_model_name(camera: &Camera) -> &'static str {
+    match camera {
+        Camera::SimplePinhole(_) => "SIMPLE_PINHOLE",
+        Camera::Pinhole(_) => "PINHOLE",
+    }
+}
+
+impl fmt::Debug for InfoRunner {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("InfoRunner")
+            .field("arguments", &self.arguments)
+            .field("cameras.len()", &self.cameras.len())
+            .field("images.len()", &self.images.len())
+            .field("point_errors.len()", &self.point_errors.len())
+            .finish()
+    }
+}
+
+impl fmt::Display for DatasetInfo {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let counts = |counts: &BTreeMap<String, usize>| {
+            counts
+                .iter()
+                .map(|(key, count)| format!("{key} x {count}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let statistics = |statistics: &Option<Statistics>| {
+            statistics
+                .map(|statistics| statistics.to_string())
+                .unwrap_or_else(|| "unknown".into())
+        };
+        let [min, max] = self.bounding_box;
+        let [x, y, z] = self.center;
+
+        writeln!(f, "| Dataset          | {:?}", self.source_path)?;
+        writeln!(
+            f,
+            "| Cameras          | {} | {}",
+            self.camera_count,
+            counts(&self.camera_models)
+        )?;
+        writeln!(
+            f,
+            "| Images           | {} | {}",
+            self.image_count,
+            counts(&self.image_sizes)
+        )?;
+        writeln!(
+            f,
+            "| Focal length X   | {}",
+            statistics(&self.focal_lengths_x)
+        )?;
+        writeln!(
+            f,
+            "| Focal length Y   | {}",
+            statistics(&self.focal_lengths_y)
+        )?;
+        writeln!(
+            f,
+            "| Bounding box     | [{:.3}, {:.3}, {:.3}] - [{:.3}, {:.3}, {:.3}]",
+            min[0], min[1], min[2], max[0], max[1], max[2],
+        )?;
+        writeln!(
+            f,
+            "| Center & radius  | [{x:.3}, {y:.3}, {z:.3}] | {:.3}",
+            self.radius
+        )?;
+        writeln!(f, "| Points           | {}", self.point_count)?;
+        writeln!(f, "| Point error (px) | {}", statistics(&self.point_errors))?;
+        write!(
+            f,
+            "| Split            | {} train | {} test",
+            self.train_count,
+            self.test_names.len()
+        )?;
+        if !self.test_names.is_empty() {
+            write!(f, " | {}", self.test_names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Statistics {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "min {:.3} | mean {:.3} | median {:.3} | max {:.3}",
+            self.min, self.mean, self.median, self.max
+        )
+    }
+}
@@ -0,0 +1,108 @@
+//! Dataset runner.
+
+pub mod check;
+pub mod export;
+pub mod info;
+pub mod nerfstudio;
+pub mod transform;
+
+pub use super::*;
+pub use command::dataset::{DatasetCommand, DatasetCommonArguments};
+pub use gausplat::loader::function::{Decoder, Encoder};
+
+use gausplat::loader::source::file::{File, Opener};
+use std::path::{Path, PathBuf};
+
+/// ## Returns
+///
+/// `(sparse_model_directory, images_directory)` of the COLMAP dataset.
+pub fn get_colmap_directories(arguments: &DatasetCommonArguments) -> (PathBuf, PathBuf) {
+    let source_path = arguments.source_path.as_path();
+    let sparse_model_directory = [source_path, "sparse".as_ref(), "0".as_ref()]
+        .iter()
+        .collect::<PathBuf>();
+    let images_directory = source_path.join(&arguments.images);
+
+    (sparse_model_directory, images_directory)
+}
+
+/// Return the directory which the image file names are relative to.
+///
+/// It is the dataset directory for Nerfstudio,
+/// whose image file paths include the image sub-directory.
+pub fn get_images_directory(arguments: &DatasetCommonArguments) -> PathBuf {
+    use command::dataset::SourceType::*;
+
+    match arguments.source_type {
+        Colmap => get_colmap_directories(arguments).1,
+        Nerfstudio => arguments.source_path.to_owned(),
+    }
+}
+
+/// Decode the data from the file.
+#[inline]
+pub fn decode_file<D: Decoder>(file_path: impl AsRef<Path>) -> Result<D, Report> {
+    Ok(D::decode(&mut File::open(file_path.as_ref())?)?)
+}
+
+/// Encode the data to the file.
+#[inline]
+pub fn encode_file<E: Encoder>(
+    file_path: impl AsRef<Path>,
+    data: &E,
+) -> Result<(), Report> {
+    Ok(data.encode(File::open(file_path.as_ref())?.truncate()?)?)
+}
+
+/// Return the rotation matrix of the unit `quaternion`.
+///
+/// `quaternion` is in the order of `[w, x, y, z]`.
+pub fn get_rotation_from_quaternion(quaternion: [f64; 4]) -> [[f64; 3]; 3] {
+    let norm = quaternion.iter().map(|q| q * q).sum::<f64>().sqrt();
+    let [w, x, y, z] = quaternion.map(|q| q / norm);
+
+    [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - w * z),
+            2.0 * (x * z + w * y),
+        ],
+        [
+            2.0 * (x * y + w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - w * x),
+        ],
+        [
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ]
+}
+
+/// Return the unit quaternion of the `rotation` matrix.
+///
+/// The quaternion is in the order of `[w, x, y, z]` with non-negative `w`.
+pub fn get_quaternion_from_rotation(rotation: [[f64; 3]; 3]) -> [f64; 4] {
+    let [[r00, r01, r02], [r10, r11, r12], [r20, r21, r22]] = rotation;
+    let trace = r00 + r11 + r22;
+
+    // NOTE: The largest component is computed first for numerical stability.
+    let quaternion = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [0.25 * s, (r21 - r12) / s, (r02 - r20) / s, (r10 - r01) / s]
+    } else if r00 > r11 && r00 > r22 {
+        let s = (1.0 + r00 - r11 - r22).sqrt() * 2.0;
+        [(r21 - r12) / s, 0.25 * s, (r01 + r10) / s, (r02 + r20) / s]
+    } else if r11 > r22 {
+        let s = (1.0 + r11 - r00 - r22).sqrt() * 2.0;
+        [(r02 - r20) / s, (r01 + r10) / s, 0.25 * s, (r12 + r21) / s]
+    } else {
+        let s = (1.0 + r22 - r00 - r11).sqrt() * 2.0;
+        [(r10 - r01) / s, (r02 + r20) / s, (r12 + r21) / s, 0.25 * s]
+    };
+
+    let sign = if quaternion[0] < 0.0 { -1.0 } else { 1.0 };
+    let norm = quaternion.iter().map(|q| q * q).sum::<f64>().sqrt() * sign;
+    quaternion.map(|q| q / norm)
+}
@@ -0,0 +1,743 @@
+//! Nerfstudio dataset format.
+//!
+//! The cameras are described in `transforms.json`,
+//! whose transform matrices are camera-to-world in the OpenGL convention.
+
+pub use super::*;
+
+use color_eyre::eyre::eyre;
+use gausplat::loader::source::colmap::{
+    Camera, Cameras, Image, Images, PinholeCamera, Point, Points,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{BufReader, BufWriter, Write},
+};
+
+/// File name of the transforms.
+pub const TRANSFORMS_FILE_NAME: &str = "transforms.json";
+
+/// File name of the initial points written by the exporter.
+pub const POINTS_FILE_NAME: &str = "sparse_pc.ply";
+
+/// Directory name of the undistorted images in the dataset directory.
+pub const UNDISTORTED_DIRECTORY_NAME: &str = "undistorted";
+
+/// Signs to convert the camera axes between the OpenCV and OpenGL conventions.
+pub const AXIS_SIGNS: [f64; 3] = [1.0, -1.0, -1.0];
+
+/// Content of `transforms.json`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Transforms {
+    /// Camera model name, e.g. `"OPENCV"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_model: Option<String>,
+    /// Intrinsics shared by the frames.
+    #[serde(flatten)]
+    pub intrinsics: Intrinsics,
+    /// Frames of the cameras.
+    pub frames: Vec<Frame>,
+    /// Initial points file path relative to the dataset directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ply_file_path: Option<PathBuf>,
+}
+
+/// Frame in `transforms.json`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Frame {
+    /// Image file path relative to the dataset directory.
+    pub file_path: PathBuf,
+    /// Camera-to-world transform in the OpenGL convention.
+    pub transform_matrix: [[f64; 4]; 4],
+    /// Intrinsics overriding the shared ones.
+    #[serde(flatten)]
+    pub intrinsics: Intrinsics,
+}
+
+/// Camera intrinsics and distortion in `transforms.json`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Intrinsics {
+    /// Horizontal focal length in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fl_x: Option<f64>,
+    /// Vertical focal length in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fl_y: Option<f64>,
+    /// Horizontal principal point in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cx: Option<f64>,
+    /// Vertical principal point in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cy: Option<f64>,
+    /// Image width in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub w: Option<u64>,
+    /// Image height in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub h: Option<u64>,
+    /// Radial distortion coefficients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k1: Option<f64>,
+    /// Radial distortion coefficients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k2: Option<f64>,
+    /// Radial distortion coefficients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k3: Option<f64>,
+    /// Radial distortion coefficients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k4: Option<f64>,
+    /// Tangential distortion coefficients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p1: Option<f64>,
+    /// Tangential distortion coefficients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p2: Option<f64>,
+}
+
+/// Lens distortion of the camera.
+///
+/// The coefficients are the same as in [`Intrinsics`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distortion {
+    /// Camera model of `"OPENCV"`.
+    ///
+    /// `[k1, k2, k3, k4, p1, p2]`, where `k4` is the radial coefficient
+    /// in the denominator as in OpenCV.
+    Opencv([f64; 6]),
+    /// Camera model of `"OPENCV_FISHEYE"`.
+    ///
+    /// `[k1, k2, k3, k4]`
+    OpencvFisheye([f64; 4]),
+}
+
+impl Transforms {
+    /// Read the transforms from the dataset directory.
+    pub fn read(source_path: impl AsRef<Path>) -> Result<Self, Report> {
+        let file_path = source_path.as_ref().join(TRANSFORMS_FILE_NAME);
+        Ok(serde_json::from_reader(BufReader::new(
+            fs::File::open(&file_path)
+                .map_err(|err| eyre!("{file_path:?} cannot be opened: {err}"))?,
+        ))?)
+    }
+
+    /// Write the transforms to the dataset directory.
+    pub fn write(
+        &self,
+        source_path: impl AsRef<Path>,
+    ) -> Result<PathBuf, Report> {
+        let file_path = source_path.as_ref().join(TRANSFORMS_FILE_NAME);
+        serde_json::to_writer_pretty(
+            BufWriter::new(fs::File::create(&file_path)?),
+            self,
+        )?;
+        Ok(file_path)
+    }
+
+    /// Convert the transforms to the COLMAP cameras, images, and points.
+    ///
+    /// The image file names are the frame file paths relative to the dataset directory.
+    ///
+    /// The cameras are pinhole, so the images of the distorted frames are undistorted
+    /// with the same intrinsics into [`UNDISTORTED_DIRECTORY_NAME`].
+    /// The existing undistorted images are reused.
+    pub fn into_colmap(
+        self,
+        source_path: impl AsRef<Path>,
+    ) -> Result<(Cameras, Images, Points), Report> {
+        let source_path = source_path.as_ref();
+        let mut cameras = Cameras::with_capacity(self.frames.len());
+        let mut images = Images::with_capacity(self.frames.len());
+
+        for (index, frame) in self.frames.into_iter().enumerate() {
+            // NOTE: Every frame has its own camera.
+            let id = index as u32 + 1;
+            let intrinsics = frame.intrinsics.or(&self.intrinsics);
+            let (width, height) = intrinsics
+                .w
+                .zip(intrinsics.h)
+                .ok_or_else(|| eyre!("No image size for {:?}", frame.file_path))?;
+            let (focal_length_x, focal_length_y) =
+                intrinsics
+                    .fl_x
+                    .zip(intrinsics.fl_y)
+                    .ok_or_else(|| eyre!("No focal length for {:?}", frame.file_path))?;
+            let camera = PinholeCamera {
+                camera_id: id,
+                width,
+                height,
+                focal_length_x,
+                focal_length_y,
+                principal_point_x: intrinsics.cx.unwrap_or(width as f64 / 2.0),
+                principal_point_y: intrinsics.cy.unwrap_or(height as f64 / 2.0),
+            };
+
+            // Undistorting the image
+            let mut file_name = get_relative_file_name(&frame.file_path)
+                .ok_or_else(|| eyre!("Invalid image file path: {:?}", frame.file_path))?;
+            let distortion = intrinsics
+                .get_distortion(self.camera_model.as_deref())
+                .map_err(|err| eyre!("{err} for {:?}", frame.file_path))?;
+            if let Some(distortion) = distortion {
+                let file_name_undistorted =
+                    format!("{UNDISTORTED_DIRECTORY_NAME}/{file_name}");
+                let file_path = source_path.join(&file_name_undistorted);
+                if !file_path.is_file() {
+                    undistort_image(
+                        source_path.join(&file_name),
+                        &file_path,
+                        &camera,
+                        &distortion,
+                    )?;
+                }
+                file_name = file_name_undistorted;
+            }
+            cameras.insert(id, Camera::Pinhole(camera));
+
+            // R_w2c = R_c2w^T, T_w2c = -R_w2c * C
+            let matrix = frame.transform_matrix;
+            let rotation =
+                [0, 1, 2].map(|i| [0, 1, 2].map(|j| matrix[j][i] * AXIS_SIGNS[i]));
+            let translation = [0, 1, 2]
+                .map(|i| -(0..3).map(|j| rotation[i][j] * matrix[j][3]).sum::<f64>());
+            images.insert(
+                id,
+                Image {
+                    image_id: id,
+                    quaternion: get_quaternion_from_rotation(rotation),
+                    translation,
+                    camera_id: id,
+                    file_name,
+                },
+            );
+        }
+
+        let points = match self.ply_file_path {
+            Some(ply_file_path) => read_points(source_path.join(ply_file_path))?,
+            None => Points::new(),
+        };
+
+        Ok((cameras, images, points))
+    }
+
+    /// Convert the COLMAP cameras and images to the transforms.
+    ///
+    /// The image file paths are prefixed with `images_directory`.
+    pub fn from_colmap(
+        cameras: &Cameras,
+        images: &Images,
+        images_directory: impl AsRef<Path>,
+    ) -> Result<Self, Report> {
+        let frames = images
+            .values()
+            .map(|image| {
+                let camera = cameras.get(&image.camera_id).ok_or_else(|| {
+                    eyre!("Camera {} has no intrinsics", image.camera_id)
+                })?;
+
+                // R_c2w = R_w2c^T, C = -R_c2w * T_w2c
+                let rotation = get_rotation_from_quaternion(image.quaternion);
+                let mut transform_matrix = [[0.0; 4]; 4];
+                transform_matrix[3][3] = 1.0;
+                (0..3).for_each(|i| {
+                    (0..3).for_each(|j| {
+                        transform_matrix[i][j] = rotation[j][i] * AXIS_SIGNS[j];
+                    });
+                    transform_matrix[i][3] = -(0..3)
+                        .map(|j| rotation[j][i] * image.translation[j])
+                        .sum::<f64>();
+                });
+
+                Ok(Frame {
+                    file_path: images_directory.as_ref().join(&image.file_name),
+                    transform_matrix,
+                    intrinsics: Intrinsics {
+                        fl_x: Some(camera.focal_length_x()),
+                        fl_y: Some(camera.focal_length_y()),
+                        cx: Some(camera.principal_point_x()),
+                        cy: Some(camera.principal_point_y()),
+                        w: Some(camera.width()),
+                        h: Some(camera.height()),
+                        ..Default::default()
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, Report>>()?;
+
+        Ok(Self {
+            camera_model: Some("PINHOLE".into()),
+            frames,
+            ..Default::default()
+        })
+    }
+}
+
+impl Intrinsics {
+    /// Return the intrinsics with the unset values taken from `other`.
+    pub fn or(
+        self,
+        other: &Self,
+    ) -> Self {
+        Self {
+            fl_x: self.fl_x.or(other.fl_x),
+            fl_y: self.fl_y.or(other.fl_y),
+            cx: self.cx.or(other.cx),
+            cy: self.cy.or(other.cy),
+            w: self.w.or(other.w),
+            h: self.h.or(other.h),
+            k1: self.k1.or(other.k1),
+            k2: self.k2.or(other.k2),
+            k3: self.k3.or(other.k3),
+            k4: self.k4.or(other.k4),
+            p1: self.p1.or(other.p1),
+            p2: self.p2.or(other.p2),
+        }
+    }
+
+    /// Return `true` if any distortion coefficient is non-zero.
+    pub fn is_distorted(&self) -> bool {
+        [self.k1, self.k2, self.k3, self.k4, self.p1, self.p2]
+            .into_iter()
+            .flatten()
+            .any(|coefficient| coefficient != 0.0)
+    }
+
+    /// Return the distortion of the `camera_model`, which defaults to `"OPENCV"`.
+    ///
+    /// It is `None` if the intrinsics are not distorted.
+    pub fn get_distortion(
+        &self,
+        camera_model: Option<&str>,
+    ) -> Result<Option<Distortion>, Report> {
+        if !self.is_distorted() {
+            return Ok(None);
+        }
+
+        let [k1, k2, k3, k4, p1, p2] =
+            [self.k1, self.k2, self.k3, self.k4, self.p1, self.p2]
+                .map(Option::unwrap_or_default);
+        match camera_model.unwrap_or("OPENCV") {
+            "OPENCV" => Ok(Some(Distortion::Opencv([k1, k2, k3, k4, p1, p2]))),
+            "OPENCV_FISHEYE" if p1 == 0.0 && p2 == 0.0 => {
+                Ok(Some(Distortion::OpencvFisheye([k1, k2, k3, k4])))
+            },
+            "OPENCV_FISHEYE" => Err(eyre!(
+                "The fisheye distortion has no tangential coefficients"
+            )),
+            camera_model => {
+                Err(eyre!("The distortion of {camera_model:?} is not supported"))
+            },
+        }
+    }
+}
+
+impl Distortion {
+    /// Return the distorted `point` in normalized image coordinates.
+    pub fn distort(
+        &self,
+        point: [f64; 2],
+    ) -> [f64; 2] {
+        let [x, y] = point;
+        let r2 = x * x + y * y;
+        match *self {
+            Self::Opencv([k1, k2, k3, k4, p1, p2]) => {
+                let radial = (1.0 + r2 * (k1 + r2 * (k2 + r2 * k3))) / (1.0 + r2 * k4);
+                [
+                    x * radial + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+                    y * radial + p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+                ]
+            },
+            Self::OpencvFisheye([k1, k2, k3, k4]) => {
+                let r = r2.sqrt();
+                if r == 0.0 {
+                    return point;
+                }
+                let theta = r.atan();
+                let theta2 = theta * theta;
+                let theta_distorted = theta
+                    * (1.0 + theta2 * (k1 + theta2 * (k2 + theta2 * (k3 + theta2 * k4))));
+                let scale = theta_distorted / r;
+                [x * scale, y * scale]
+            },
+        }
+    }
+}
+
+/// Undistort the image from `source` to `target` for the pinhole `camera`.
+///
+/// The pixels outside the distorted image are black.
+pub fn undistort_image(
+    source: impl AsRef<Path>,
+    target: impl AsRef<Path>,
+    camera: &PinholeCamera,
+    distortion: &Distortion,
+) -> Result<(), Report> {
+    use image::{DynamicImage, ImageBuffer, Pixel};
+
+    fn remap<P: Pixel<Subpixel = u8>>(
+        image: &ImageBuffer<P, Vec<u8>>,
+        camera: &PinholeCamera,
+        distortion: &Distortion,
+    ) -> ImageBuffer<P, Vec<u8>> {
+        let (width, height) = image.dimensions();
+        let count = P::CHANNEL_COUNT as usize;
+        ImageBuffer::from_fn(width, height, |u, v| {
+            let point = [
+                (u as f64 - camera.principal_point_x) / camera.focal_length_x,
+                (v as f64 - camera.principal_point_y) / camera.focal_length_y,
+            ];
+            let [x, y] = distortion.distort(point);
+            let u = x * camera.focal_length_x + camera.principal_point_x;
+            let v = y * camera.focal_length_y + camera.principal_point_y;
+            let mut channels = [0; 4];
+            if u >= 0.0 && v >= 0.0 && u <= (width - 1) as f64 && v <= (height - 1) as f64
+            {
+                // NOTE: The pixel is sampled bilinearly.
+                let (u0, v0) = (u.floor() as u32, v.floor() as u32);
+                let (u1, v1) = ((u0 + 1).min(width - 1), (v0 + 1).min(height - 1));
+                let (s, t) = (u - u0 as f64, v - v0 as f64);
+                let [p00, p01, p10, p11] = [(u0, v0), (u1, v0), (u0, v1), (u1, v1)]
+                    .map(|(u, v)| image.get_pixel(u, v).channels());
+                (0..count).for_each(|c| {
+                    let value = (p00[c] as f64 * (1.0 - s) + p01[c] as f64 * s)
+                        * (1.0 - t)
+                        + (p10[c] as f64 * (1.0 - s) + p11[c] as f64 * s) * t;
+                    channels[c] = value.round() as u8;
+                });
+            }
+            *P::from_slice(&channels[..count])
+        })
+    }
+
+    let source = source.as_ref();
+    let target = target.as_ref();
+    let image = image::open(source)
+        .map_err(|err| eyre!("{source:?} cannot be decoded: {err}"))?;
+    if (image.width() as u64, image.height() as u64) != (camera.width, camera.height) {
+        return Err(eyre!(
+            "{source:?} is {}x{}, but the camera is {}x{}",
+            image.width(),
+            image.height(),
+            camera.width,
+            camera.height,
+        ));
+    }
+    let image = if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(remap(&image.to_rgba8(), camera, distortion))
+    } else {
+        DynamicImage::ImageRgb8(remap(&image.to_rgb8(), camera, distortion))
+    };
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    image
+        .save(target)
+        .map_err(|err| eyre!("{target:?} cannot be encoded: {err}"))?;
+    log::info!(
+        target: "gausplat::scepter::dataset::nerfstudio",
+        "undistort {source:?} to {target:?}",
+    );
+
+    Ok(())
+}
+
+/// Return the `file_path` joined by `/`.
+///
+/// It is `None` if the `file_path` is empty, absolute or has parent components.
+pub fn get_relative_file_name(file_path: &Path) -> Option<String> {
+    use std::path::Component;
+
+    let names = file_path
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    (!names.is_empty()).then(|| names.join("/"))
+}
+
+/// Read the points from the PLY file.
+///
+/// The vertices should have `x`, `y`, `z`, and optionally `red`, `green`, `blue`.
+/// The floating-point colors are in `[0, 1]`.
+pub fn read_points(file_path: impl AsRef<Path>) -> Result<Points, Report> {
+    use ply_rs::{parser::Parser, ply::DefaultElement, ply::Property};
+
+    let file_path = file_path.as_ref();
+    let ply = Parser::<DefaultElement>::new()
+        .read_ply(&mut BufReader::new(fs::File::open(file_path)?))
+        .map_err(|err| eyre!("{file_path:?} cannot be decoded: {err}"))?;
+
+    let get = |vertex: &DefaultElement, key: &str| {
+        Some(match vertex.get(key)? {
+            Property::Char(v) => *v as f64,
+            Property::UChar(v) => *v as f64,
+            Property::Short(v) => *v as f64,
+            Property::UShort(v) => *v as f64,
+            Property::Int(v) => *v as f64,
+            Property::UInt(v) => *v as f64,
+            Property::Float(v) => *v as f64,
+            Property::Double(v) => *v,
+            _ => return None,
+        })
+    };
+
+    ply.payload
+        .get("vertex")
+        .map(|vertices| {
+            vertices
+                .iter()
+                .map(|vertex| {
+                    let position = ["x", "y", "z"].map(|key| get(vertex, key));
+                    if position.contains(&None) {
+                        return Err(eyre!("No vertex position in {file_path:?}"));
+                    }
+                    Ok(Point {
+                        position: position.map(Option::unwrap_or_default),
+                        color_rgb: ["red", "green", "blue"].map(|key| {
+                            match vertex.get(key) {
+                                Some(Property::Float(_) | Property::Double(_)) => {
+                                    (get(vertex, key).unwrap_or_default() * 255.0).round()
+                                        as u8
+                                },
+                                _ => get(vertex, key).unwrap_or(128.0) as u8,
+                            }
+                        }),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| Ok(Points::new()))
+}
+
+/// Write the points to the PLY file in ASCII.
+pub fn write_points(
+    file_path: impl AsRef<Path>,
+    points: &Points,
+) -> Result<(), Report> {
+    let mut writer = BufWriter::new(fs::File::create(file_path)?);
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "element vertex {}", points.len())?;
+    for property in ["x", "y", "z"] {
+        writeln!(writer, "property double {property}")?;
+    }
+    for property in ["red", "green", "blue"] {
+        writeln!(writer, "property uchar {property}")?;
+    }
+    writeln!(writer, "end_header")?;
+    for Point {
+        position: [x, y, z],
+        color_rgb: [r, g, b],
+        ..
+    } in points
+    {
+        writeln!(writer, "{x} {y} {z} {r} {g} {b}")?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_frame(file_path: &str) -> Frame {
+        Frame {
+            file_path: file_path.into(),
+            transform_matrix: [
+                [0.0, 0.0, 1.0, 1.0],
+                [1.0, 0.0, 0.0, 2.0],
+                [0.0, 1.0, 0.0, 3.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn get_transforms(frames: Vec<Frame>) -> Transforms {
+        Transforms {
+            intrinsics: Intrinsics {
+                fl_x: Some(500.0),
+                fl_y: Some(400.0),
+                w: Some(640),
+                h: Some(480),
+                ..Default::default()
+            },
+            frames,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn get_relative_file_name_valid() {
+        let name = get_relative_file_name(Path::new("./images_2/frame_00001.png"));
+        assert_eq!(name.as_deref(), Some("images_2/frame_00001.png"));
+
+        let name = get_relative_file_name(Path::new("frame_00001.png"));
+        assert_eq!(name.as_deref(), Some("frame_00001.png"));
+    }
+
+    #[test]
+    fn get_relative_file_name_invalid() {
+        assert_eq!(get_relative_file_name(Path::new("")), None);
+        assert_eq!(get_relative_file_name(Path::new("/images/a.png")), None);
+        assert_eq!(get_relative_file_name(Path::new("../images/a.png")), None);
+    }
+
+    #[test]
+    fn into_colmap_with_shared_intrinsics() {
+        let transforms = get_transforms(vec![get_frame("images_2/frame_00001.png")]);
+
+        let (cameras, images, points) = transforms.into_colmap("").unwrap();
+        assert!(points.is_empty());
+
+        let camera = cameras.get(&1).unwrap();
+        assert_eq!((camera.width(), camera.height()), (640, 480));
+        assert_eq!(camera.focal_length_x(), 500.0);
+        assert_eq!(camera.focal_length_y(), 400.0);
+        assert_eq!(camera.principal_point_x(), 320.0);
+        assert_eq!(camera.principal_point_y(), 240.0);
+
+        let image = images.get(&1).unwrap();
+        assert_eq!(image.file_name, "images_2/frame_00001.png");
+        assert_eq!(image.camera_id, 1);
+    }
+
+    #[test]
+    fn into_colmap_with_distortion() {
+        let source_path = std::env::temp_dir().join(format!(
+            "gausplat-scepter-nerfstudio-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(source_path.join("images")).unwrap();
+        image::RgbImage::from_pixel(640, 480, image::Rgb([10, 20, 30]))
+            .save(source_path.join("images/frame_00001.png"))
+            .unwrap();
+
+        let mut frame = get_frame("images/frame_00001.png");
+        frame.intrinsics.k1 = Some(0.5);
+        let transforms = get_transforms(vec![frame]);
+
+        let (cameras, images, _) = transforms.into_colmap(&source_path).unwrap();
+        assert!(matches!(cameras.get(&1), Some(Camera::Pinhole(_))));
+        let file_name = &images.get(&1).unwrap().file_name;
+        assert_eq!(file_name, "undistorted/images/frame_00001.png");
+
+        // NOTE: The center is kept, and the corners are outside the distorted image.
+        let image = image::open(source_path.join(file_name)).unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (640, 480));
+        assert_eq!(image.get_pixel(320, 240), &image::Rgb([10, 20, 30]));
+        assert_eq!(image.get_pixel(0, 0), &image::Rgb([0, 0, 0]));
+
+        fs::remove_dir_all(&source_path).unwrap();
+    }
+
+    #[test]
+    fn into_colmap_with_unsupported_distortion() {
+        let mut frame = get_frame("images/frame_00001.png");
+        frame.intrinsics.k1 = Some(0.1);
+        let mut transforms = get_transforms(vec![frame]);
+        transforms.camera_model = Some("EQUIRECTANGULAR".into());
+
+        assert!(transforms.into_colmap("").is_err());
+    }
+
+    #[test]
+    fn distort() {
+        let point = [0.3, -0.2];
+        let r2 = 0.13;
+
+        let distortion = Distortion::Opencv([0.1, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let [x, y] = distortion.distort(point);
+        assert!((x - 0.3 * (1.0 + 0.1 * r2)).abs() < 1e-12, "{x}");
+        assert!((y + 0.2 * (1.0 + 0.1 * r2)).abs() < 1e-12, "{y}");
+
+        let distortion = Distortion::Opencv([0.0, 0.0, 0.0, 0.0, 0.01, 0.0]);
+        let [x, y] = distortion.distort(point);
+        assert!((x - (0.3 + 2.0 * 0.01 * 0.3 * -0.2)).abs() < 1e-12, "{x}");
+        assert!((y - (-0.2 + 0.01 * (r2 + 2.0 * 0.04))).abs() < 1e-12, "{y}");
+
+        // NOTE: The fisheye projection without coefficients is equidistant.
+        let distortion = Distortion::OpencvFisheye([0.0; 4]);
+        let [x, y] = distortion.distort(point);
+        let scale = f64::sqrt(r2).atan() / f64::sqrt(r2);
+        assert!((x - 0.3 * scale).abs() < 1e-12, "{x}");
+        assert!((y + 0.2 * scale).abs() < 1e-12, "{y}");
+        assert_eq!(distortion.distort([0.0, 0.0]), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn read_points_with_float_colors() {
+        let file_path = std::env::temp_dir().join(format!(
+            "gausplat-scepter-nerfstudio-points-{}.ply",
+            std::process::id()
+        ));
+        fs::write(
+            &file_path,
+            "ply\nformat ascii 1.0\nelement vertex 1\n\
+            property float x\nproperty float y\nproperty float z\n\
+            property float red\nproperty float green\nproperty float blue\n\
+            end_header\n1 2 3 1.0 0.5 0.0\n",
+        )
+        .unwrap();
+
+        let points = read_points(&file_path).unwrap();
+        assert_eq!(points.len(), 1);
+        let point = points.iter().next().unwrap();
+        assert_eq!(point.position, [1.0, 2.0, 3.0]);
+        assert_eq!(point.color_rgb, [255, 128, 0]);
+
+        fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn into_colmap_without_intrinsics() {
+        let transforms = Transforms {
+            frames: vec![get_frame("images/frame_00001.png")],
+            ..Default::default()
+        };
+
+        assert!(transforms.into_colmap("").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let transforms = get_transforms(vec![
+            get_frame("frame_00001.png"),
+            get_frame("sub/frame_00002.png"),
+        ]);
+
+        let (cameras, images, _) = transforms.to_owned().into_colmap("").unwrap();
+        let transforms_output =
+            Transforms::from_colmap(&cameras, &images, "images").unwrap();
+
+        assert_eq!(transforms_output.frames.len(), 2);
+        transforms_output
+            .frames
+            .iter()
+            .zip(transforms.frames.iter())
+            .for_each(|(output, target)| {
+                assert_eq!(
+                    output.file_path,
+                    Path::new("images").join(&target.file_path)
+                );
+                output
+                    .transform_matrix
+                    .iter()
+                    .flatten()
+                    .zip(target.transform_matrix.iter().flatten())
+                    .for_each(|(output, target)| {
+                        assert!((output - target).abs() < 1e-9, "{output} != {target}");
+                    });
+                assert_eq!(output.intrinsics.fl_x, Some(500.0));
+                assert_eq!(output.intrinsics.cx, Some(320.0));
+            });
+    }
+}
@@ -0,0 +1,601 @@
+//! Similarity transform of scenes.
+
+pub use super::*;
+pub use command::gaussian_3d::UpAxis;
+
+use gausplat::loader::source::colmap::{Images, Points};
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::PI,
+    fs,
+    io::{self, BufReader, BufWriter},
+};
+
+/// Maximum degree of the spherical harmonics basis.
+pub const SH_BASIS_DEGREE_MAX: usize = 3;
+
+/// Similarity transform from the dataset frame to the scene frame.
+///
+/// `x' = scale * rotation * x + translation`
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SceneTransform {
+    /// Rotation matrix in row-major order.
+    pub rotation: [[f64; 3]; 3],
+    /// Uniform scaling factor.
+    pub scale: f64,
+    /// Translation vector.
+    pub translation: [f64; 3],
+}
+
+impl SceneTransform {
+    /// File name of the scene transform in the model directory.
+    pub const FILE_NAME: &str = "scene_transform.json";
+
+    /// Return the transform normalizing the scene of the `images`.
+    ///
+    /// It centers the camera centers at the origin, scales them to a unit radius,
+    /// and rotates the average camera up vector to the `up_axis`.
+    pub fn init_normalization(
+        images: &Images,
+        up_axis: UpAxis,
+    ) -> Self {
+        let rotations = images
+            .values()
+            .map(|image| get_rotation_from_quaternion(image.quaternion))
+            .collect::<Vec<_>>();
+        let count = rotations.len().max(1) as f64;
+
+        // C = -R^T * T
+        let centers = images
+            .values()
+            .zip(&rotations)
+            .map(|(image, rotation)| {
+                [0, 1, 2].map(|i| {
+                    -(0..3)
+                        .map(|j| rotation[j][i] * image.translation[j])
+                        .sum::<f64>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let center = [0, 1, 2].map(|i| centers.iter().map(|c| c[i]).sum::<f64>() / count);
+        let radius = centers
+            .iter()
+            .map(|c| {
+                (0..3)
+                    .map(|i| (c[i] - center[i]).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .fold(0.0, f64::max);
+        let scale = if radius > 0.0 { 1.0 / radius } else { 1.0 };
+
+        // NOTE: The camera Y axis points downward in COLMAP.
+        let up = [0, 1, 2].map(|i| -rotations.iter().map(|r| r[1][i]).sum::<f64>());
+        let axis = match up_axis {
+            UpAxis::Y => [0.0, 1.0, 0.0],
+            UpAxis::Z => [0.0, 0.0, 1.0],
+        };
+        let rotation = get_rotation_between(up, axis);
+
+        // t = -s * R * C
+        let translation = [0, 1, 2]
+            .map(|i| -scale * (0..3).map(|j| rotation[i][j] * center[j]).sum::<f64>());
+
+        Self {
+            rotation,
+            scale,
+            translation,
+        }
+    }
+
+    /// Return the inverse transform.
+    pub fn inverse(&self) -> Self {
+        let scale = 1.0 / self.scale;
+        let rotation = [0, 1, 2].map(|i| [0, 1, 2].map(|j| self.rotation[j][i]));
+        let translation = [0, 1, 2].map(|i| {
+            -scale
+                * (0..3)
+                    .map(|j| rotation[i][j] * self.translation[j])
+                    .sum::<f64>()
+        });
+
+        Self {
+            rotation,
+            scale,
+            translation,
+        }
+    }
+
+    /// Transform the `position`.
+    #[inline]
+    pub fn transform_position(
+        &self,
+        position: [f64; 3],
+    ) -> [f64; 3] {
+        [0, 1, 2].map(|i| {
+            self.scale
+                * (0..3)
+                    .map(|j| self.rotation[i][j] * position[j])
+                    .sum::<f64>()
+                + self.translation[i]
+        })
+    }
+
+    /// Transform the poses of the `images`.
+    ///
+    /// The camera space is scaled as well, so the images are unchanged.
+    pub fn transform_images(
+        &self,
+        images: &mut Images,
+    ) {
+        images.values_mut().for_each(|image| {
+            // R' = R * R0^T, T' = s * T - R' * T0
+            let rotation_source = get_rotation_from_quaternion(image.quaternion);
+            let rotation = [0, 1, 2].map(|i| {
+                [0, 1, 2].map(|j| {
+                    (0..3)
+                        .map(|k| rotation_source[i][k] * self.rotation[j][k])
+                        .sum::<f64>()
+                })
+            });
+            image.translation = [0, 1, 2].map(|i| {
+                self.scale * image.translation[i]
+                    - (0..3)
+                        .map(|j| rotation[i][j] * self.translation[j])
+                        .sum::<f64>()
+            });
+            image.quaternion = get_quaternion_from_rotation(rotation);
+        });
+    }
+
+    /// Transform the positions of the `points`.
+    pub fn transform_points(
+        &self,
+        points: &mut Points,
+    ) {
+        points.iter_mut().for_each(|point| {
+            point.position = self.transform_position(point.position);
+        });
+    }
+
+    /// Transform the Gaussians in the flattened values.
+    ///
+    /// ## Arguments
+    ///
+    /// * `positions` - `[P, 3]`
+    /// * `rotations` - `[P, 4]` in the quaternion order of `[x, y, z, w]`
+    /// * `scalings` - `[P, 3]`
+    /// * `colors_sh` - `[P, (D + 1) ^ 2, 3]`, where `D` is the SH degree
+    pub fn transform_gaussians(
+        &self,
+        positions: &mut [f64],
+        rotations: &mut [f64],
+        scalings: &mut [f64],
+        colors_sh: &mut [f64],
+    ) {
+        let point_count = positions.len() / 3;
+        if point_count == 0 {
+            return;
+        }
+
+        positions.chunks_exact_mut(3).for_each(|position| {
+            let output = self.transform_position([position[0], position[1], position[2]]);
+            position.copy_from_slice(&output);
+        });
+
+        // q' = q0 * q
+        let [w0, x0, y0, z0] = get_quaternion_from_rotation(self.rotation);
+        rotations.chunks_exact_mut(4).for_each(|rotation| {
+            let [x, y, z, w] = [rotation[0], rotation[1], rotation[2], rotation[3]];
+            rotation.copy_from_slice(&[
+                w0 * x + x0 * w + y0 * z - z0 * y,
+                w0 * y + y0 * w + z0 * x - x0 * z,
+                w0 * z + z0 * w + x0 * y - y0 * x,
+                w0 * w - x0 * x - y0 * y - z0 * z,
+            ]);
+        });
+
+        scalings
+            .iter_mut()
+            .for_each(|scaling| *scaling *= self.scale);
+
+        // NOTE: The DC coefficients are independent of the view direction.
+        let sh_count = colors_sh.len() / point_count / 3;
+        let sh_rotations = (1..=SH_BASIS_DEGREE_MAX)
+            .take_while(|degree| (degree + 1) * (degree + 1) <= sh_count)
+            .filter_map(|degree| get_sh_rotation(self.rotation, degree))
+            .collect::<Vec<_>>();
+        if sh_rotations.is_empty() {
+            return;
+        }
+        colors_sh.chunks_exact_mut(sh_count * 3).for_each(|colors| {
+            sh_rotations.iter().zip(1..).for_each(|(matrix, degree)| {
+                let offset = degree * degree;
+                let count = matrix.len();
+                (0..3).for_each(|channel| {
+                    let input = (0..count)
+                        .map(|j| colors[(offset + j) * 3 + channel])
+                        .collect::<Vec<_>>();
+                    (0..count).for_each(|i| {
+                        colors[(offset + i) * 3 + channel] =
+                            (0..count).map(|j| matrix[i][j] * input[j]).sum::<f64>();
+                    });
+                });
+            });
+        });
+    }
+
+    /// Load the transform from the model directory.
+    pub fn load(directory: impl AsRef<Path>) -> Result<Self, Report> {
+        let file_path = directory.as_ref().join(Self::FILE_NAME);
+        Ok(serde_json::from_reader(BufReader::new(fs::File::open(
+            file_path,
+        )?))?)
+    }
+
+    /// Remove the transform from the model directory if it exists.
+    pub fn remove(directory: impl AsRef<Path>) -> Result<(), Report> {
+        match fs::remove_file(directory.as_ref().join(Self::FILE_NAME)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Save the transform to the model directory.
+    pub fn save(
+        &self,
+        directory: impl AsRef<Path>,
+    ) -> Result<PathBuf, Report> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory)?;
+
+        let file_path = directory.join(Self::FILE_NAME);
+        serde_json::to_writer_pretty(
+            BufWriter::new(fs::File::create(&file_path)?),
+            self,
+        )?;
+
+        Ok(file_path)
+    }
+}
+
+/// Return the rotation matrix rotating the direction `from` to the direction `to`.
+pub fn get_rotation_between(
+    from: [f64; 3],
+    to: [f64; 3],
+) -> [[f64; 3]; 3] {
+    let normalize = |v: [f64; 3]| {
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            v.map(|x| x / norm)
+        } else {
+            v
+        }
+    };
+    let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let a = normalize(from);
+    let b = normalize(to);
+    if a == [0.0; 3] || b == [0.0; 3] {
+        return identity;
+    }
+
+    let cross = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    let cos = (0..3).map(|i| a[i] * b[i]).sum::<f64>();
+
+    // NOTE: The opposite directions are rotated by 180 degrees
+    // around a perpendicular axis.
+    if cos < -1.0 + 1e-9 {
+        let helper = if a[0].abs() < 0.9 {
+            [1.0, 0.0, 0.0]
+        } else {
+            [0.0, 1.0, 0.0]
+        };
+        let axis = normalize([
+            a[1] * helper[2] - a[2] * helper[1],
+            a[2] * helper[0] - a[0] * helper[2],
+            a[0] * helper[1] - a[1] * helper[0],
+        ]);
+        return [0, 1, 2]
+            .map(|i| [0, 1, 2].map(|j| 2.0 * axis[i] * axis[j] - identity[i][j]));
+    }
+
+    // R = I + [v]x + [v]x^2 / (1 + cos)
+    let skew = [
+        [0.0, -cross[2], cross[1]],
+        [cross[2], 0.0, -cross[0]],
+        [-cross[1], cross[0], 0.0],
+    ];
+    [0, 1, 2].map(|i| {
+        [0, 1, 2].map(|j| {
+            identity[i][j]
+                + skew[i][j]
+                + (0..3).map(|k| skew[i][k] * skew[k][j]).sum::<f64>() / (1.0 + cos)
+        })
+    })
+}
+
+/// Return the real spherical harmonics basis of the `degree` in the `direction`.
+///
+/// The basis is in the order and the sign convention of 3DGS.
+/// It is `None` if the `degree` exceeds [`SH_BASIS_DEGREE_MAX`].
+pub fn get_sh_basis(
+    direction: [f64; 3],
+    degree: usize,
+) -> Option<Vec<f64>> {
+    const C1: f64 = 0.4886025119029199;
+    const C2: [f64; 5] = [
+        1.0925484305920792,
+        -1.0925484305920792,
+        0.31539156525252005,
+        -1.0925484305920792,
+        0.5462742152960396,
+    ];
+    const C3: [f64; 7] = [
+        -0.5900435899266435,
+        2.890611442640554,
+        -0.4570457994644658,
+        0.3731763325901154,
+        -0.4570457994644658,
+        1.445305721320277,
+        -0.5900435899266435,
+    ];
+
+    let [x, y, z] = direction;
+    let (xx, yy, zz) = (x * x, y * y, z * z);
+    match degree {
+        0 => Some(vec![0.28209479177387814]),
+        1 => Some(vec![-C1 * y, C1 * z, -C1 * x]),
+        2 => Some(vec![
+            C2[0] * x * y,
+            C2[1] * y * z,
+            C2[2] * (2.0 * zz - xx - yy),
+            C2[3] * x * z,
+            C2[4] * (xx - yy),
+        ]),
+        3 => Some(vec![
+            C3[0] * y * (3.0 * xx - yy),
+            C3[1] * x * y * z,
+            C3[2] * y * (4.0 * zz - xx - yy),
+            C3[3] * z * (2.0 * zz - 3.0 * xx - 3.0 * yy),
+            C3[4] * x * (4.0 * zz - xx - yy),
+            C3[5] * z * (xx - yy),
+            C3[6] * x * (xx - 3.0 * yy),
+        ]),
+        _ => None,
+    }
+}
+
+/// Return the matrix transforming the SH coefficients of the `degree`
+/// for the `rotation` of the scene.
+///
+/// The colors of the coefficients `M * c` in the direction `d`
+/// equal the colors of the coefficients `c` in the direction `R^T * d`.
+/// It is `None` if the `degree` exceeds [`SH_BASIS_DEGREE_MAX`].
+pub fn get_sh_rotation(
+    rotation: [[f64; 3]; 3],
+    degree: usize,
+) -> Option<Vec<Vec<f64>>> {
+    let count = 2 * degree + 1;
+
+    // NOTE: The matrix is fitted on the directions of a Fibonacci sphere,
+    // which has more samples than the coefficients.
+    let sample_count = count * 4;
+    let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+    let (bases, bases_rotated): (Vec<_>, Vec<_>) = (0..sample_count)
+        .map(|index| {
+            let z = 1.0 - (2.0 * index as f64 + 1.0) / sample_count as f64;
+            let r = (1.0 - z * z).sqrt();
+            let angle = golden_angle * index as f64;
+            let direction = [r * angle.cos(), r * angle.sin(), z];
+            let direction_rotated = [0, 1, 2]
+                .map(|i| (0..3).map(|j| rotation[j][i] * direction[j]).sum::<f64>());
+            Some((
+                get_sh_basis(direction, degree)?,
+                get_sh_basis(direction_rotated, degree)?,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    // A^T * A * M = A^T * B
+    let mut system = (0..count)
+        .map(|i| {
+            (0..count * 2)
+                .map(|j| {
+                    bases
+                        .iter()
+                        .zip(&bases_rotated)
+                        .map(|(a, b)| a[i] * if j < count { a[j] } else { b[j - count] })
+                        .sum::<f64>()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Solving by the Gauss-Jordan elimination with partial pivoting
+    (0..count).for_each(|column| {
+        let pivot = (column..count)
+            .max_by(|&a, &b| system[a][column].abs().total_cmp(&system[b][column].abs()))
+            .unwrap_or(column);
+        system.swap(column, pivot);
+        let value = system[column][column];
+        system[column].iter_mut().for_each(|v| *v /= value);
+        (0..count).filter(|&row| row != column).for_each(|row| {
+            let factor = system[row][column];
+            (0..count * 2).for_each(|j| {
+                system[row][j] -= factor * system[column][j];
+            });
+        });
+    });
+
+    Some(
+        system
+            .into_iter()
+            .map(|row| row[count..].to_vec())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gausplat::loader::source::colmap::Image;
+
+    fn assert_close(
+        output: &[f64],
+        target: &[f64],
+    ) {
+        assert_eq!(output.len(), target.len());
+        output.iter().zip(target).for_each(|(output, target)| {
+            assert!((output - target).abs() < 1e-9, "{output:?} != {target:?}");
+        });
+    }
+
+    fn get_transform() -> SceneTransform {
+        SceneTransform {
+            rotation: get_rotation_from_quaternion([0.9, 0.1, -0.3, 0.2]),
+            scale: 0.5,
+            translation: [1.0, -2.0, 3.0],
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let transform = get_transform();
+        let position = [0.3, -0.7, 1.1];
+
+        let output = transform
+            .inverse()
+            .transform_position(transform.transform_position(position));
+        assert_close(&output, &position);
+    }
+
+    #[test]
+    fn init_normalization() {
+        let images = [[1.0, 0.0, 0.0], [-1.0, 2.0, 0.0], [3.0, 1.0, -1.0]]
+            .into_iter()
+            .enumerate()
+            .map(|(index, translation)| {
+                let id = index as u32 + 1;
+                let image = Image {
+                    image_id: id,
+                    quaternion: [1.0, 0.0, 0.0, 0.0],
+                    translation,
+                    camera_id: 1,
+                    file_name: format!("{id}.png"),
+                };
+                (id, image)
+            })
+            .collect::<Images>();
+        let mut images_output = images.to_owned();
+
+        let transform = SceneTransform::init_normalization(&images, UpAxis::Z);
+        transform.transform_images(&mut images_output);
+
+        // The camera centers are centered at the origin within a unit radius.
+        let centers = images_output
+            .values()
+            .map(|image| {
+                let rotation = get_rotation_from_quaternion(image.quaternion);
+                [0, 1, 2].map(|i| {
+                    -(0..3)
+                        .map(|j| rotation[j][i] * image.translation[j])
+                        .sum::<f64>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let center = [0, 1, 2].map(|i| centers.iter().map(|c| c[i]).sum::<f64>() / 3.0);
+        let radius = centers
+            .iter()
+            .map(|c| c.iter().map(|v| v * v).sum::<f64>().sqrt())
+            .fold(0.0, f64::max);
+        assert_close(&center, &[0.0; 3]);
+        assert_close(&[radius], &[1.0]);
+
+        // The camera up vector, i.e. -Y, is the +Z axis.
+        images_output.values().for_each(|image| {
+            let rotation = get_rotation_from_quaternion(image.quaternion);
+            assert_close(&rotation[1].map(|v| -v), &[0.0, 0.0, 1.0]);
+        });
+    }
+
+    #[test]
+    fn get_rotation_between_opposite() {
+        let rotation = get_rotation_between([0.0, 1.0, 0.0], [0.0, -1.0, 0.0]);
+        let output = [0, 1, 2].map(|i| rotation[i][1]);
+        assert_close(&output, &[0.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn get_sh_rotation_consistency() {
+        let rotation = get_transform().rotation;
+        let direction = [0.48, -0.6, 0.64];
+        let direction_rotated =
+            [0, 1, 2].map(|i| (0..3).map(|j| rotation[j][i] * direction[j]).sum::<f64>());
+
+        (1..=SH_BASIS_DEGREE_MAX).for_each(|degree| {
+            let matrix = get_sh_rotation(rotation, degree).unwrap();
+            let coefficients = (0..matrix.len())
+                .map(|i| (i as f64 * 0.37).sin())
+                .collect::<Vec<_>>();
+            let coefficients_output = matrix
+                .iter()
+                .map(|row| row.iter().zip(&coefficients).map(|(m, c)| m * c).sum())
+                .collect::<Vec<f64>>();
+
+            let color = |coefficients: &[f64], direction| {
+                get_sh_basis(direction, degree)
+                    .unwrap()
+                    .iter()
+                    .zip(coefficients)
+                    .map(|(y, c)| y * c)
+                    .sum::<f64>()
+            };
+            assert_close(
+                &[color(&coefficients_output, direction)],
+                &[color(&coefficients, direction_rotated)],
+            );
+        });
+
+        assert_eq!(get_sh_basis(direction, SH_BASIS_DEGREE_MAX + 1), None);
+        assert_eq!(get_sh_rotation(rotation, SH_BASIS_DEGREE_MAX + 1), None);
+    }
+
+    #[test]
+    fn transform_gaussians_round_trip() {
+        let transform = get_transform();
+        let positions = vec![0.1, 0.2, 0.3, -1.0, 0.5, 2.0];
+        let rotations = vec![0.0, 0.0, 0.0, 1.0, 0.5, -0.5, 0.5, 0.5];
+        let scalings = vec![0.1, 0.2, 0.3, 1.0, 1.0, 1.0];
+        let colors_sh = (0..2 * 16 * 3)
+            .map(|i| (i as f64 * 0.11).cos())
+            .collect::<Vec<_>>();
+
+        let mut values = (
+            positions.to_owned(),
+            rotations.to_owned(),
+            scalings.to_owned(),
+            colors_sh.to_owned(),
+        );
+        [transform, transform.inverse()]
+            .iter()
+            .for_each(|transform| {
+                transform.transform_gaussians(
+                    &mut values.0,
+                    &mut values.1,
+                    &mut values.2,
+                    &mut values.3,
+                );
+            });
+
+        assert_close(&values.0, &positions);
+        assert_close(&values.1, &rotations);
+        assert_close(&values.2, &scalings);
+        assert_close(&values.3, &colors_sh);
+    }
+}
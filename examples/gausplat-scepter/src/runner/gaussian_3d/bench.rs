@@ -0,0 +1,291 @@
+//! Benchmarking runner for 3DGS.
+
+pub use super::*;
+pub use command::gaussian_3d::{BenchArguments, BenchDevice};
+
+use burn::tensor::backend::Backend;
+use color_eyre::eyre::eyre;
+use serde::Serialize;
+use std::{fmt, path::PathBuf, time::Instant};
+
+/// Benchmarking runner.
+#[derive(Clone)]
+pub struct BenchRunner {
+    /// Arguments for benchmarking.
+    pub arguments: BenchArguments,
+    /// Cameras for benchmarking.
+    ///
+    /// They are for testing if available, otherwise for training.
+    pub cameras: Cameras,
+    /// Device for rendering.
+    pub device: WgpuDevice,
+    /// The iteration to benchmark.
+    pub iteration: u64,
+    /// Model file path.
+    pub model_file_path: PathBuf,
+    /// Scene for benchmarking.
+    pub scene: Gaussian3dScene<Wgpu>,
+}
+
+/// Results of the benchmark.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BenchResult {
+    /// Model file path.
+    pub model_file_path: PathBuf,
+    /// The benchmarked iteration.
+    pub iteration: u64,
+    /// Device for rendering.
+    pub device: BenchDevice,
+    /// Number of Gaussians.
+    pub point_count: usize,
+    /// Number of cameras.
+    pub camera_count: usize,
+    /// Results at each scale.
+    pub scales: Vec<BenchScaleResult>,
+}
+
+/// Results of the benchmark at a scale.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BenchScaleResult {
+    /// Scale of the image resolution.
+    pub scale: f64,
+    /// Maximum side length of the images in pixels.
+    pub size_max: u32,
+    /// Number of timed frames.
+    pub frame_count: usize,
+    /// Mean of the frame times in milliseconds.
+    pub frame_time_mean: f64,
+    /// 95th percentile of the frame times in milliseconds.
+    pub frame_time_p95: f64,
+    /// Frames per second of the mean frame time.
+    pub fps: f64,
+    /// Mean times of the stages if timed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stages: Option<BenchStageResult>,
+}
+
+/// Mean times of the rendering stages in milliseconds.
+///
+/// The frame time is the sum of the submitting and executing times.
+/// The stages are timed on the host, not per kernel on the device.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BenchStageResult {
+    /// Recording and submitting the commands on the host.
+    pub submit: f64,
+    /// Waiting for the device to execute the commands.
+    pub execute: f64,
+    /// Reading the rendered image back to the host.
+    pub readback: f64,
+}
+
+impl BenchArguments {
+    /// Initialize the benchmarking runner.
+    pub fn init(&self) -> Result<BenchRunner, Report> {
+        let arguments = self.to_owned();
+
+        // Checking the arguments
+
+        if self
+            .scales
+            .iter()
+            .any(|scale| !(scale.is_finite() && *scale > 0.0))
+        {
+            return Err(eyre!("The scales should be positive"));
+        }
+        if self.repeat == 0 {
+            return Err(eyre!("The number of passes should be positive"));
+        }
+
+        // Loading the cameras
+
+        // NOTE: The cameras are in the frame of the model.
+        let (cameras_test, cameras_train, _, _) =
+            get_cameras_and_points_of_model(&self.common_arguments)?;
+        let cameras = if cameras_test.is_empty() {
+            cameras_train
+        } else {
+            cameras_test
+        };
+        if cameras.is_empty() {
+            return Err(eyre!("There are no cameras to benchmark"));
+        }
+
+        let (iteration, model_file_path) =
+            get_model_file_path(&self.common_arguments.model_path, self.iteration)?;
+
+        // Loading the scene
+
+        let device = match self.device {
+            BenchDevice::Gpu => WgpuDevice::default(),
+            BenchDevice::Software => WgpuDevice::Cpu,
+        };
+        let scene =
+            Gaussian3dScene::decode_polygon(&mut File::open(&model_file_path)?, &device)?;
+
+        Ok(BenchRunner {
+            arguments,
+            cameras,
+            device,
+            iteration,
+            model_file_path,
+            scene,
+        })
+    }
+}
+
+impl BenchRunner {
+    /// Benchmark the rendering at the `scale`.
+    pub fn bench_scale(
+        &self,
+        scale: f64,
+        options: &Gaussian3dRenderOptions,
+    ) -> Result<BenchScaleResult, Report> {
+        // Rescaling the cameras
+
+        let mut cameras = self.cameras.to_owned();
+        if scale != 1.0 {
+            cameras.par_values_mut().try_for_each(|camera| {
+                let size = (camera.size_max() as f64 * scale).round() as u32;
+                camera.resize_max(size.max(1))
+            })?;
+        }
+        let size_max = cameras.values().map(|camera| camera.size_max()).max();
+
+        // Warming up
+
+        cameras
+            .values()
+            .cycle()
+            .take(self.arguments.warmup as usize)
+            .try_for_each(|camera| {
+                self.scene.render(&camera.view, options)?;
+                Ok::<_, Report>(())
+            })?;
+        Wgpu::sync(&self.device);
+
+        // Timing the frames
+
+        // NOTE: The rendering is synchronized to time each frame.
+        let can_time_stages = self.arguments.stages;
+        let mut stage_times = vec![];
+        let mut frame_times = cameras
+            .values()
+            .cycle()
+            .take(cameras.len() * self.arguments.repeat as usize)
+            .map(|camera| {
+                let time = Instant::now();
+                let output = self.scene.render(&camera.view, options)?;
+                let time_submit = time.elapsed();
+                Wgpu::sync(&self.device);
+                let time_execute = time.elapsed();
+                if can_time_stages {
+                    output.colors_rgb_2d.into_data();
+                    let time_readback = time.elapsed();
+                    stage_times.push(
+                        [
+                            time_submit,
+                            time_execute - time_submit,
+                            time_readback - time_execute,
+                        ]
+                        .map(|time| time.as_secs_f64() * 1e3),
+                    );
+                }
+                Ok(time_execute.as_secs_f64() * 1e3)
+            })
+            .collect::<Result<Vec<_>, Report>>()?;
+        frame_times.sort_unstable_by(f64::total_cmp);
+
+        let frame_count = frame_times.len();
+        let frame_time_mean = frame_times.iter().sum::<f64>() / frame_count as f64;
+        // NOTE: The index is in bounds since the count is positive.
+        let frame_time_p95 =
+            frame_times[((frame_count as f64 * 0.95).ceil() as usize).max(1) - 1];
+
+        Ok(BenchScaleResult {
+            scale,
+            size_max: size_max.unwrap_or_default(),
+            frame_count,
+            frame_time_mean,
+            frame_time_p95,
+            fps: 1e3 / frame_time_mean,
+            stages: can_time_stages.then(|| {
+                let [submit, execute, readback] = [0, 1, 2].map(|stage| {
+                    stage_times.iter().map(|times| times[stage]).sum::<f64>()
+                        / frame_count as f64
+                });
+                BenchStageResult {
+                    submit,
+                    execute,
+                    readback,
+                }
+            }),
+        })
+    }
+}
+
+impl Runner for BenchRunner {
+    fn run(self) -> Result<(), Report> {
+        // Specifying the parameters
+
+        let options_renderer = Gaussian3dRenderOptions::default()
+            .with_colors_sh_degree_max(self.arguments.sh_degree);
+        let point_count = self.scene.point_count();
+
+        // Benchmarking at each scale
+
+        let mut result = BenchResult {
+            model_file_path: self.model_file_path.to_owned(),
+            iteration: self.iteration,
+            device: self.arguments.device,
+            point_count,
+            camera_count: self.cameras.len(),
+            scales: Vec::with_capacity(self.arguments.scales.len()),
+        };
+        for &scale in &self.arguments.scales {
+            let result_scale = self.bench_scale(scale, &options_renderer)?;
+            if !self.arguments.json {
+                eprintln!(
+                    "| Benching 3DGS | {point_count} points | {:?} | \
+                    Scale {scale} ({} px) | \
+                    Mean {:.3} ms | P95 {:.3} ms | {:.1} FPS |",
+                    self.arguments.device,
+                    result_scale.size_max,
+                    result_scale.frame_time_mean,
+                    result_scale.frame_time_p95,
+                    result_scale.fps,
+                );
+                if let Some(stages) = &result_scale.stages {
+                    eprintln!(
+                        "| Benching 3DGS | Stages | \
+                        Submit {:.3} ms | Execute {:.3} ms | Readback {:.3} ms |",
+                        stages.submit, stages.execute, stages.readback,
+                    );
+                }
+            }
+            result.scales.push(result_scale);
+        }
+
+        if self.arguments.json {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BenchRunner {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("BenchRunner")
+            .field("arguments", &self.arguments)
+            .field("cameras.len()", &self.cameras.len())
+            .field("device", &self.device)
+            .field("iteration", &self.iteration)
+            .field("model_file_path", &self.model_file_path)
+            .field("scene", &self.scene)
+            .finish()
+    }
+}
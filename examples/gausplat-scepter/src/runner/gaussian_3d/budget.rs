@@ -0,0 +1,168 @@
+//! Point budget for 3DGS training.
+
+pub use super::*;
+
+use color_eyre::eyre::eyre;
+use gausplat::trainer::train::gaussian_3d::{Autodiff, RefinerConfig};
+
+/// Units of the readable sizes in bytes.
+pub const SIZE_UNITS: &[(&str, f64)] = &[
+    ("B", 1.0),
+    ("kB", 1e3),
+    ("KB", 1e3),
+    ("MB", 1e6),
+    ("GB", 1e9),
+    ("TB", 1e12),
+    ("KiB", 1024.0),
+    ("MiB", 1048576.0),
+    ("GiB", 1073741824.0),
+    ("TiB", 1099511627776.0),
+];
+
+/// Budget of the points in the scene.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointBudget {
+    /// Maximum number of points.
+    pub point_count_max: Option<u64>,
+    /// Threshold of the densification within the budget.
+    pub threshold: f64,
+}
+
+impl PointBudget {
+    /// Maximum factor of the point count in a refinement.
+    ///
+    /// Each candidate is either cloned or split into two.
+    pub const GROWTH_FACTOR_MAX: u64 = 2;
+
+    /// Initialize the budget of `point_count_max` points
+    /// with the densification `threshold`.
+    #[inline]
+    pub fn init(
+        point_count_max: Option<u64>,
+        threshold: f64,
+    ) -> Self {
+        Self {
+            point_count_max,
+            threshold,
+        }
+    }
+
+    /// Return the densification threshold for the next refinement
+    /// of `point_count` points.
+    ///
+    /// It is infinite if the refinement may exceed the budget,
+    /// so only the pruning takes effect.
+    pub fn get_threshold(
+        &self,
+        point_count: u64,
+    ) -> f64 {
+        match self.point_count_max {
+            Some(point_count_max)
+                if point_count.saturating_mul(Self::GROWTH_FACTOR_MAX)
+                    > point_count_max =>
+            {
+                f64::INFINITY
+            },
+            _ => self.threshold,
+        }
+    }
+
+    /// Update the densification threshold of the `config` for the `scene`
+    /// and log the size projected to the budget.
+    ///
+    /// It is called before training and after each refinement.
+    pub fn update(
+        &self,
+        config: &mut RefinerConfig,
+        scene: &Gaussian3dScene<Autodiff<Wgpu>>,
+        iteration: u64,
+    ) -> Result<(), Report> {
+        let point_count = scene.point_count() as u64;
+        if let Some(point_count_max) = self.point_count_max {
+            if point_count > point_count_max {
+                return Err(eyre!(
+                    "The scene has {point_count} points, \
+                    which exceeds the budget of {point_count_max} points"
+                ));
+            }
+        }
+
+        let threshold = self.get_threshold(point_count);
+        config.threshold_position_2d_grad_norm = threshold;
+
+        // NOTE: The size is linear in the number of points.
+        let size = scene.size_readable();
+        let point_count_max = self.point_count_max.unwrap_or(point_count);
+        let size_projected = parse_size_readable(&size)
+            .map(|size| {
+                let size_per_point = size / point_count.max(1) as f64;
+                get_size_readable(size_per_point * point_count_max as f64)
+            })
+            .unwrap_or_else(|| "unknown size".into());
+        log::info!(
+            target: "gausplat::scepter::gaussian_3d::budget",
+            "{size} with {point_count} points at iteration {iteration}, \
+            projected to {size_projected} with {point_count_max} points",
+        );
+        if threshold.is_infinite() {
+            log::info!(
+                target: "gausplat::scepter::gaussian_3d::budget",
+                "pause densification at iteration {iteration}",
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the bytes of the readable `size`, e.g. "217.3 MB" or "1.5 GiB".
+pub fn parse_size_readable(size: &str) -> Option<f64> {
+    let size = size.trim();
+    let position = size.find(|c: char| c.is_ascii_alphabetic())?;
+    let (value, unit) = size.split_at(position);
+    let value = value.trim().parse::<f64>().ok()?;
+    let (_, factor) = SIZE_UNITS.iter().find(|(name, _)| *name == unit.trim())?;
+    Some(value * factor)
+}
+
+/// Return the readable size of the `size` in bytes with the decimal units.
+pub fn get_size_readable(size: f64) -> String {
+    let (unit, factor) = ["TB", "GB", "MB", "kB"]
+        .into_iter()
+        .zip([1e12, 1e9, 1e6, 1e3])
+        .find(|(_, factor)| size >= *factor)
+        .unwrap_or(("B", 1.0));
+    format!("{:.1} {unit}", size / factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_threshold() {
+        let budget = PointBudget::init(Some(1000), 0.0002);
+        assert_eq!(budget.get_threshold(0), 0.0002);
+        assert_eq!(budget.get_threshold(500), 0.0002);
+        assert_eq!(budget.get_threshold(501), f64::INFINITY);
+        assert_eq!(budget.get_threshold(u64::MAX), f64::INFINITY);
+
+        let budget = PointBudget::init(None, 0.0002);
+        assert_eq!(budget.get_threshold(u64::MAX), 0.0002);
+    }
+
+    #[test]
+    fn parse_size_readable_and_get_size_readable() {
+        let size = parse_size_readable("217.3 MB").unwrap();
+        assert!((size - 217.3e6).abs() < 1e-3, "{size}");
+        assert_eq!(parse_size_readable("0.0 B"), Some(0.0));
+        assert_eq!(parse_size_readable("1.5GiB"), Some(1.5 * 1073741824.0));
+        assert_eq!(parse_size_readable("1.5 XB"), None);
+        assert_eq!(parse_size_readable("MB"), None);
+        assert_eq!(parse_size_readable(""), None);
+
+        assert_eq!(get_size_readable(217.3e6), "217.3 MB");
+        assert_eq!(get_size_readable(999.0), "999.0 B");
+        assert_eq!(get_size_readable(1.5e9), "1.5 GB");
+    }
+}
@@ -0,0 +1,796 @@
+//! Training callbacks for 3DGS.
+
+pub use super::*;
+pub use command::gaussian_3d::EarlyStoppingMetric;
+
+use gausplat::trainer::{
+    metric::{MeanStructuralSimilarity, Metric, Psnr},
+    train::gaussian_3d::{Autodiff, AutodiffModule},
+};
+use progress::{ProgressEvent, ProgressWriter};
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    fmt,
+    path::Path,
+    rc::Rc,
+    time::Instant,
+};
+use train::{Stopped, TrainRunner};
+
+/// Hooks of the training loop.
+///
+/// The hooks are called in the order of [`TrainCallback::on_step`],
+/// [`TrainCallback::on_densify`], [`TrainCallback::on_sh_degree_increase`],
+/// [`TrainCallback::on_eval`] and [`TrainCallback::on_save`] at each iteration.
+/// [`TrainCallback::on_start`] and [`TrainCallback::on_end`] are called
+/// before and after the loop.
+///
+/// The hooks receive the read-only [`TrainContext`],
+/// whose loss terms after the step are evaluated on demand
+/// by [`TrainContext::loss_after_step`].
+///
+/// Returning an error stops the training,
+/// e.g. [`Stopped`] stops it without failure.
+/// The other callbacks are still called with the same hook before it stops,
+/// but not after any other error.
+pub trait TrainCallback {
+    /// It is called before the first iteration.
+    #[allow(unused_variables)]
+    fn on_start(
+        &mut self,
+        iterations: u64,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// It is called after each optimization step.
+    #[allow(unused_variables)]
+    fn on_step(
+        &mut self,
+        context: &TrainContext,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// It is called at the densification iterations.
+    #[allow(unused_variables)]
+    fn on_densify(
+        &mut self,
+        context: &TrainContext,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// It is called at the iterations increasing the SH degree.
+    #[allow(unused_variables)]
+    fn on_sh_degree_increase(
+        &mut self,
+        context: &TrainContext,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// Return `true` if the testing dataset should be evaluated at the `iteration`.
+    #[allow(unused_variables)]
+    fn should_eval(
+        &self,
+        iteration: u64,
+    ) -> bool {
+        false
+    }
+
+    /// It is called after evaluating the testing dataset.
+    ///
+    /// The evaluation is shared by all callbacks.
+    /// It is skipped if there are no cameras for testing.
+    #[allow(unused_variables)]
+    fn on_eval(
+        &mut self,
+        context: &TrainContext,
+        mssim: f64,
+        psnr: f64,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// Return `true` if the model should be saved at the `iteration`.
+    #[allow(unused_variables)]
+    fn should_save(
+        &self,
+        iteration: u64,
+    ) -> bool {
+        false
+    }
+
+    /// It is called after saving the model to the `model_file_path`.
+    #[allow(unused_variables)]
+    fn on_save(
+        &mut self,
+        context: &TrainContext,
+        model_file_path: &Path,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// It is called after saving the model to the `model_file_path`
+    /// at the interruption after the `iteration`.
+    #[allow(unused_variables)]
+    fn on_save_interrupted(
+        &mut self,
+        iteration: u64,
+        model_file_path: &Path,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+
+    /// It is called after the loop with the `result` of training.
+    #[allow(unused_variables)]
+    fn on_end(
+        &mut self,
+        result: &Result<(), Report>,
+    ) -> Result<(), Report> {
+        Ok(())
+    }
+}
+
+/// Read-only state of the training loop for callbacks.
+pub struct TrainContext<'c> {
+    /// The finished iteration.
+    pub iteration: u64,
+    /// The camera of the iteration.
+    pub camera: &'c Camera,
+    /// Cameras for testing.
+    pub cameras_test: &'c Cameras,
+    /// Model directory path.
+    pub model_path: &'c Path,
+    /// Options for rendering.
+    pub options_renderer: &'c Gaussian3dRenderOptions,
+    /// Scene in training.
+    pub scene: &'c Gaussian3dScene<Autodiff<Wgpu>>,
+    /// Readable size of the scene.
+    pub size: &'c str,
+    /// Metric for the loss terms.
+    pub(super) metric_mssim: &'c MeanStructuralSimilarity<Wgpu, 3>,
+    /// Progress bar of the training.
+    pub(super) bar: &'c RefCell<Bar>,
+    /// It is set when the progress bar is refreshed for messages.
+    pub(super) has_refreshed_bar: Cell<bool>,
+    /// Loss terms of the camera after the step, evaluated on demand.
+    pub(super) loss_after_step: OnceCell<TrainLoss>,
+}
+
+/// Loss terms of the camera after the optimization step at an iteration.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TrainLoss {
+    /// Mean absolute error, i.e., the L1 term.
+    pub l1: f64,
+    /// Structural dissimilarity, i.e., one minus the M-SSIM.
+    pub d_ssim: f64,
+}
+
+/// Callback showing the training progress.
+pub struct ProgressCallback {
+    /// Progress bar of the training.
+    pub bar: Rc<RefCell<Bar>>,
+    /// Enabling the PSNR details.
+    pub can_show_details: bool,
+    /// Metric for the PSNR details.
+    pub metric_psnr: Psnr<Wgpu>,
+}
+
+/// Callback writing the JSON progress events.
+#[derive(Debug)]
+pub struct JsonProgressCallback {
+    /// Number of iterations between the step events.
+    pub interval: u64,
+    /// Total number of iterations.
+    pub iterations: u64,
+    /// The time of starting.
+    pub time: Instant,
+    /// Writer of the events.
+    pub writer: ProgressWriter,
+}
+
+/// Callback showing the testing metrics.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestCallback {
+    /// Iterations for testing in reversed order.
+    pub iterations_reversed: Vec<u64>,
+}
+
+/// Callback showing the saved models.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SaveCallback {
+    /// Iterations for saving in reversed order.
+    pub iterations_reversed: Vec<u64>,
+    /// Enabling the messages.
+    pub can_show_save: bool,
+}
+
+/// Callback stopping without improvement on the testing dataset.
+///
+/// The model of the best iteration is saved and recorded as the best iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EarlyStoppingCallback {
+    /// Number of iterations between evaluations.
+    pub interval: u64,
+    /// Number of evaluations without improvement before stopping.
+    pub patience: u64,
+    /// Metric to improve.
+    pub metric: EarlyStoppingMetric,
+    /// `(iteration, score)` of the best evaluation.
+    pub best: (u64, f64),
+    /// Number of evaluations without improvement.
+    pub count_without_improvement: u64,
+}
+
+/// Callback stopping after the time budget.
+///
+/// The model is saved and tested before stopping.
+#[derive(Clone, Debug)]
+pub struct DurationCallback {
+    /// Maximum duration in seconds.
+    pub max_duration: f64,
+    /// Enabling the testing.
+    pub can_show_test: bool,
+    /// The time of starting.
+    pub time: Instant,
+    /// It is set when the duration is reached.
+    pub is_over: bool,
+}
+
+impl<'c> TrainContext<'c> {
+    /// Initialize the context at the `iteration`.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn init(
+        iteration: u64,
+        camera: &'c Camera,
+        cameras_test: &'c Cameras,
+        model_path: &'c Path,
+        options_renderer: &'c Gaussian3dRenderOptions,
+        scene: &'c Gaussian3dScene<Autodiff<Wgpu>>,
+        size: &'c str,
+        metric_mssim: &'c MeanStructuralSimilarity<Wgpu, 3>,
+        bar: &'c RefCell<Bar>,
+    ) -> Self {
+        Self {
+            iteration,
+            camera,
+            cameras_test,
+            model_path,
+            options_renderer,
+            scene,
+            size,
+            metric_mssim,
+            bar,
+            has_refreshed_bar: Cell::new(false),
+            loss_after_step: OnceCell::new(),
+        }
+    }
+
+    /// Print the `message` below the progress bar.
+    pub fn eprintln(
+        &self,
+        message: impl fmt::Display,
+    ) -> Result<(), Report> {
+        // NOTE: The progress should be shown only once.
+        if !self.has_refreshed_bar.replace(true) {
+            let mut bar = self.bar.borrow_mut();
+            if !bar.disable {
+                bar.refresh()?;
+            }
+        }
+        eprintln!("{message}");
+        Ok(())
+    }
+
+    /// Return the loss terms of the camera after the optimization step.
+    ///
+    /// It is a post-step evaluation, so the terms differ from the ones
+    /// optimized by the trainer before the step.
+    /// They are evaluated on the first call, since the scene is rendered again.
+    pub fn loss_after_step(&self) -> Result<TrainLoss, Report> {
+        if let Some(loss) = self.loss_after_step.get() {
+            return Ok(*loss);
+        }
+
+        let device = self.scene.device();
+        let output = self
+            .scene
+            .valid()
+            .render(&self.camera.view, self.options_renderer)?
+            .colors_rgb_2d;
+        let target = self.camera.image.decode_rgb_tensor(&device)?;
+        let l1 = (output.to_owned() - target.to_owned())
+            .abs()
+            .mean()
+            .into_scalar() as f64;
+        let mssim = self
+            .metric_mssim
+            .evaluate(output.movedim(2, 0), target.movedim(2, 0))
+            .into_scalar() as f64;
+        let loss = TrainLoss {
+            l1,
+            d_ssim: 1.0 - mssim,
+        };
+
+        Ok(*self.loss_after_step.get_or_init(|| loss))
+    }
+}
+
+/// Call the `hook` of each callback in order.
+///
+/// [`Stopped`] is returned after all callbacks are called,
+/// while the other errors are returned immediately.
+pub fn call_hooks(
+    callbacks: &mut [Box<dyn TrainCallback>],
+    mut hook: impl FnMut(&mut dyn TrainCallback) -> Result<(), Report>,
+) -> Result<(), Report> {
+    let mut stopped = None;
+    for callback in callbacks {
+        match hook(callback.as_mut()) {
+            Err(err) if err.is::<Stopped>() => {
+                stopped.get_or_insert(err);
+            },
+            result => result?,
+        }
+    }
+    stopped.map_or(Ok(()), Err)
+}
+
+impl ProgressCallback {
+    /// Initialize the callback with the progress `bar`.
+    pub fn init(
+        bar: Rc<RefCell<Bar>>,
+        can_show_details: bool,
+        device: &WgpuDevice,
+    ) -> Self {
+        if can_show_details {
+            bar.borrow_mut().postfix = format!(" 0.0 B | PSNR {:.2} dB |", 0.0);
+        }
+        Self {
+            bar,
+            can_show_details,
+            metric_psnr: Psnr::init(device),
+        }
+    }
+}
+
+impl TrainCallback for ProgressCallback {
+    fn on_step(
+        &mut self,
+        _context: &TrainContext,
+    ) -> Result<(), Report> {
+        self.bar.borrow_mut().update(1)?;
+        Ok(())
+    }
+
+    fn on_densify(
+        &mut self,
+        context: &TrainContext,
+    ) -> Result<(), Report> {
+        if !self.can_show_details {
+            return Ok(());
+        }
+
+        // Updating the progress details
+        let device = context.scene.device();
+        let output = context
+            .scene
+            .valid()
+            .render(&context.camera.view, context.options_renderer)?
+            .colors_rgb_2d;
+        let target = context.camera.image.decode_rgb_tensor(&device)?;
+        let psnr = self.metric_psnr.evaluate(output, target).into_scalar();
+        let mut bar = self.bar.borrow_mut();
+        bar.postfix = format!(" {} | PSNR {psnr:.2} dB |", context.size);
+        if !bar.disable {
+            bar.refresh()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl JsonProgressCallback {
+    /// Initialize the callback with the `writer`.
+    pub fn init(
+        writer: ProgressWriter,
+        interval: u64,
+    ) -> Self {
+        Self {
+            interval: interval.max(1),
+            iterations: 0,
+            time: Instant::now(),
+            writer,
+        }
+    }
+}
+
+impl TrainCallback for JsonProgressCallback {
+    fn on_start(
+        &mut self,
+        iterations: u64,
+    ) -> Result<(), Report> {
+        self.iterations = iterations;
+        self.time = Instant::now();
+        self.writer.write(ProgressEvent::Start {
+            task: "train".into(),
+            total: iterations,
+        })
+    }
+
+    fn on_step(
+        &mut self,
+        context: &TrainContext,
+    ) -> Result<(), Report> {
+        if context.iteration % self.interval != 0 {
+            return Ok(());
+        }
+        self.writer.write(ProgressEvent::Step {
+            iteration: context.iteration,
+            total: self.iterations,
+            size: context.size.to_owned(),
+            elapsed: self.time.elapsed().as_secs_f64(),
+        })
+    }
+
+    fn on_eval(
+        &mut self,
+        context: &TrainContext,
+        mssim: f64,
+        psnr: f64,
+    ) -> Result<(), Report> {
+        self.writer.write(ProgressEvent::Test {
+            iteration: context.iteration,
+            psnr,
+            ssim: mssim,
+        })
+    }
+
+    fn on_save(
+        &mut self,
+        context: &TrainContext,
+        model_file_path: &Path,
+    ) -> Result<(), Report> {
+        self.writer.write(ProgressEvent::Save {
+            iteration: context.iteration,
+            path: model_file_path.to_owned(),
+        })
+    }
+
+    fn on_save_interrupted(
+        &mut self,
+        iteration: u64,
+        model_file_path: &Path,
+    ) -> Result<(), Report> {
+        self.writer.write(ProgressEvent::Save {
+            iteration,
+            path: model_file_path.to_owned(),
+        })
+    }
+
+    fn on_end(
+        &mut self,
+        result: &Result<(), Report>,
+    ) -> Result<(), Report> {
+        self.writer.write(match result {
+            Ok(()) => ProgressEvent::Complete {
+                elapsed: self.time.elapsed().as_secs_f64(),
+            },
+            Err(err) => ProgressEvent::Error {
+                message: err.to_string(),
+            },
+        })
+    }
+}
+
+impl TestCallback {
+    /// Initialize the callback with the testing `iterations`.
+    pub fn init(iterations: &[u64]) -> Self {
+        let mut iterations_reversed = iterations.to_owned();
+        iterations_reversed.sort_unstable_by(|a, b| b.cmp(a));
+        Self {
+            iterations_reversed,
+        }
+    }
+}
+
+impl TrainCallback for TestCallback {
+    fn should_eval(
+        &self,
+        iteration: u64,
+    ) -> bool {
+        self.iterations_reversed.last() == Some(&iteration)
+    }
+
+    fn on_eval(
+        &mut self,
+        context: &TrainContext,
+        mssim: f64,
+        psnr: f64,
+    ) -> Result<(), Report> {
+        if !self.should_eval(context.iteration) {
+            return Ok(());
+        }
+        self.iterations_reversed.pop();
+
+        let size = context.size.to_owned();
+        context.eprintln(format_args!(
+            "|  Testing 3DGS | {size} | PSNR {psnr:.2} dB | SSIM {mssim:.3} |"
+        ))
+    }
+}
+
+impl SaveCallback {
+    /// Initialize the callback with the saving `iterations`.
+    pub fn init(
+        iterations: &[u64],
+        can_show_save: bool,
+    ) -> Self {
+        let mut iterations_reversed = iterations.to_owned();
+        iterations_reversed.sort_unstable_by(|a, b| b.cmp(a));
+        Self {
+            iterations_reversed,
+            can_show_save,
+        }
+    }
+}
+
+impl TrainCallback for SaveCallback {
+    fn should_save(
+        &self,
+        iteration: u64,
+    ) -> bool {
+        self.iterations_reversed.last() == Some(&iteration)
+    }
+
+    fn on_save(
+        &mut self,
+        context: &TrainContext,
+        _: &Path,
+    ) -> Result<(), Report> {
+        if !self.should_save(context.iteration) {
+            return Ok(());
+        }
+        self.iterations_reversed.pop();
+
+        if self.can_show_save {
+            let size = context.size.to_owned();
+            context.eprintln(format_args!("|   Saving 3DGS | {size} |"))?;
+        }
+        Ok(())
+    }
+}
+
+impl EarlyStoppingCallback {
+    /// Initialize the callback.
+    pub fn init(
+        interval: u64,
+        patience: u64,
+        metric: EarlyStoppingMetric,
+    ) -> Self {
+        Self {
+            interval,
+            patience,
+            metric,
+            best: (0, f64::NEG_INFINITY),
+            count_without_improvement: 0,
+        }
+    }
+}
+
+impl TrainCallback for EarlyStoppingCallback {
+    fn should_eval(
+        &self,
+        iteration: u64,
+    ) -> bool {
+        iteration % self.interval == 0
+    }
+
+    fn on_eval(
+        &mut self,
+        context: &TrainContext,
+        mssim: f64,
+        psnr: f64,
+    ) -> Result<(), Report> {
+        let iteration = context.iteration;
+        if !self.should_eval(iteration) {
+            return Ok(());
+        }
+
+        let score = match self.metric {
+            EarlyStoppingMetric::Psnr => psnr,
+            EarlyStoppingMetric::Ssim => mssim,
+        };
+        log::info!(
+            target: "gausplat::scepter::gaussian_3d::train",
+            "evaluate at iteration {iteration} \
+            with PSNR {psnr:.2} dB and SSIM {mssim:.3}",
+        );
+
+        if score > self.best.1 {
+            self.best = (iteration, score);
+            self.count_without_improvement = 0;
+            TrainRunner::save_model(
+                iteration,
+                context.model_path,
+                &context.scene.valid(),
+            )?;
+            set_iteration_best(context.model_path.join("point_cloud"), Some(iteration))?;
+        } else {
+            self.count_without_improvement += 1;
+        }
+
+        if self.count_without_improvement >= self.patience {
+            let (iteration_best, score_best) = self.best;
+            let metric = self.metric;
+            let size = context.size.to_owned();
+            context.eprintln(format_args!(
+                "| Stopping 3DGS | {size} | \
+                Best iteration {iteration_best} | {metric:?} {score_best:.3} |"
+            ))?;
+            return Err(Stopped.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl DurationCallback {
+    /// Initialize the callback with the `max_duration` in seconds.
+    pub fn init(
+        max_duration: f64,
+        can_show_test: bool,
+    ) -> Self {
+        Self {
+            max_duration,
+            can_show_test,
+            time: Instant::now(),
+            is_over: false,
+        }
+    }
+}
+
+impl TrainCallback for DurationCallback {
+    fn on_step(
+        &mut self,
+        _: &TrainContext,
+    ) -> Result<(), Report> {
+        self.is_over = self.time.elapsed().as_secs_f64() >= self.max_duration;
+        Ok(())
+    }
+
+    fn should_eval(
+        &self,
+        _: u64,
+    ) -> bool {
+        self.is_over && self.can_show_test
+    }
+
+    fn on_eval(
+        &mut self,
+        context: &TrainContext,
+        mssim: f64,
+        psnr: f64,
+    ) -> Result<(), Report> {
+        if !self.should_eval(context.iteration) {
+            return Ok(());
+        }
+
+        let size = context.size.to_owned();
+        context.eprintln(format_args!(
+            "|  Testing 3DGS | {size} | PSNR {psnr:.2} dB | SSIM {mssim:.3} |"
+        ))
+    }
+
+    fn should_save(
+        &self,
+        _: u64,
+    ) -> bool {
+        self.is_over
+    }
+
+    fn on_save(
+        &mut self,
+        context: &TrainContext,
+        _: &Path,
+    ) -> Result<(), Report> {
+        if !self.is_over {
+            return Ok(());
+        }
+
+        let iteration = context.iteration;
+        let duration = self.time.elapsed().as_secs_f64();
+        let size = context.size.to_owned();
+        context.eprintln(format_args!(
+            "| Stopping 3DGS | {size} | Iteration {iteration} in {duration:.03} s |"
+        ))?;
+        Err(Stopped.into())
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("ProgressCallback")
+            .field("can_show_details", &self.can_show_details)
+            .finish()
+    }
+}
+
+impl fmt::Debug for TrainContext<'_> {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("TrainContext")
+            .field("iteration", &self.iteration)
+            .field("cameras_test.len()", &self.cameras_test.len())
+            .field("model_path", &self.model_path)
+            .field("size", &self.size)
+            .field("has_refreshed_bar", &self.has_refreshed_bar.get())
+            .field("loss", &self.loss.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_eyre::eyre::eyre;
+
+    struct StartCallback {
+        result: fn() -> Result<(), Report>,
+        count: Rc<Cell<u64>>,
+    }
+
+    impl TrainCallback for StartCallback {
+        fn on_start(
+            &mut self,
+            _iterations: u64,
+        ) -> Result<(), Report> {
+            self.count.set(self.count.get() + 1);
+            (self.result)()
+        }
+    }
+
+    #[test]
+    fn call_hooks() {
+        let count = Rc::new(Cell::new(0));
+        let get_callbacks =
+            |results: [fn() -> Result<(), Report>; 3]| -> Vec<Box<dyn TrainCallback>> {
+                results
+                    .map(|result| {
+                        Box::new(StartCallback {
+                            result,
+                            count: count.to_owned(),
+                        }) as Box<dyn TrainCallback>
+                    })
+                    .into()
+            };
+
+        // NOTE: The callbacks after the stopping one are still called.
+        let mut callbacks =
+            get_callbacks([|| Err(Stopped.into()), || Ok(()), || Err(Stopped.into())]);
+        let output = super::call_hooks(&mut callbacks, |callback| callback.on_start(1));
+        assert!(output.unwrap_err().is::<Stopped>());
+        assert_eq!(count.replace(0), 3);
+
+        // NOTE: The callbacks after the failing one are not called.
+        let mut callbacks =
+            get_callbacks([|| Err(Stopped.into()), || Err(eyre!("Failure")), || Ok(())]);
+        let output = super::call_hooks(&mut callbacks, |callback| callback.on_start(1));
+        assert!(!output.unwrap_err().is::<Stopped>());
+        assert_eq!(count.replace(0), 2);
+
+        let mut callbacks = get_callbacks([|| Ok(()), || Ok(()), || Ok(())]);
+        let output = super::call_hooks(&mut callbacks, |callback| callback.on_start(1));
+        assert!(output.is_ok());
+        assert_eq!(count.replace(0), 3);
+    }
+}
@@ -1,15 +1,28 @@
 //! 3DGS runner.
 
+pub mod bench;
+pub mod budget;
+
+pub mod callback;
 pub mod eval;
+pub mod pipeline;
 pub mod render;
+pub mod schedule;
 pub mod train;
 
-use std::path::PathBuf;
+use color_eyre::eyre::eyre;
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Write},
+    iter,
+    path::{Path, PathBuf},
+};
 
 pub use super::*;
 pub use command::{Gaussian3dCommonArguments, Gaussian3dModelCommand};
 pub use gausplat::trainer::{
-    dataset::sparse_view::{Cameras, Points, SparseViewDataset},
+    dataset::sparse_view::{Camera, Cameras, Point, Points, SparseViewDataset},
     train::gaussian_3d::{Gaussian3dRenderOptions, Gaussian3dScene, Wgpu},
 };
 
@@ -26,26 +39,70 @@ use gausplat::trainer::{
     train::gaussian_3d::{Tensor, WgpuDevice},
 };
 use rayon::{iter::ParallelIterator, slice::ParallelSliceMut};
+use runner::dataset::{nerfstudio::Transforms, transform::SceneTransform};
+
+/// File name of the best iteration record in the directory of saving iterations.
+pub const ITERATION_BEST_FILE_NAME: &str = "best_iteration.txt";
+
+/// File name of the training time record in the model directory.
+pub const TIME_TRAIN_FILE_NAME: &str = "time_train.txt";
 
 /// ## Returns
 ///
-/// `(cameras_test, cameras_train, points)`
+/// `(cameras_test, cameras_train, points, transform)`
+///
+/// The `transform` is applied to the scene if the normalization is enabled.
 pub fn get_cameras_and_points(
     arguments: &Gaussian3dCommonArguments
-) -> Result<(Cameras, Cameras, Points), Report> {
+) -> Result<(Cameras, Cameras, Points, Option<SceneTransform>), Report> {
+    get_cameras_and_points_with_transform(arguments, |images| {
+        arguments
+            .normalize
+            .map(|up_axis| SceneTransform::init_normalization(images, up_axis))
+    })
+}
+
+/// ## Returns
+///
+/// `(cameras_test, cameras_train, points, transform)`
+///
+/// The `transform` is the one saved in the model directory while training.
+/// It is applied to the scene if it exists, and the normalization is ignored.
+pub fn get_cameras_and_points_of_model(
+    arguments: &Gaussian3dCommonArguments
+) -> Result<(Cameras, Cameras, Points, Option<SceneTransform>), Report> {
+    let model_path = &arguments.model_path;
+    let transform = if model_path.join(SceneTransform::FILE_NAME).is_file() {
+        Some(SceneTransform::load(model_path)?)
+    } else {
+        if arguments.normalize.is_some() {
+            log::warn!(
+                target: "gausplat::scepter::gaussian_3d",
+                "ignore the normalization since the model is not normalized",
+            );
+        }
+        None
+    };
+
+    get_cameras_and_points_with_transform(arguments, |_| transform)
+}
+
+/// ## Returns
+///
+/// `(cameras_test, cameras_train, points, transform)`
+///
+/// The `transform` is returned by `get_transform` with the images
+/// and applied to the scene.
+fn get_cameras_and_points_with_transform(
+    arguments: &Gaussian3dCommonArguments,
+    get_transform: impl FnOnce(&colmap::Images) -> Option<SceneTransform>,
+) -> Result<(Cameras, Cameras, Points, Option<SceneTransform>), Report> {
     use command::gaussian_3d::SourceType::*;
 
-    let (dataset, test_ids) = match arguments.source_type {
+    let (cameras, mut images, mut points) = match arguments.source_type {
         Colmap => {
-            use colmap::{Cameras, ColmapSource, Images, Points};
+            use colmap::{Cameras, Images, Points};
 
-            let image_file_pattern = [
-                arguments.source_path.as_path(),
-                arguments.images.as_ref(),
-                "*.*".as_ref(),
-            ]
-            .iter()
-            .collect::<PathBuf>();
             let sparse_model_dir = [
                 arguments.source_path.as_path(),
                 "sparse".as_ref(),
@@ -59,38 +116,65 @@ pub fn get_cameras_and_points(
                 Images::decode(&mut File::open(sparse_model_dir.join("images.bin"))?)?;
             let points =
                 Points::decode(&mut File::open(sparse_model_dir.join("points3D.bin"))?)?;
-            let images_file = Files::open(image_file_pattern)?;
-            let source = ColmapSource {
-                cameras,
-                images,
-                images_file,
-                points,
-            };
-
-            let mut dataset = SparseViewDataset::init_from_colmap(source)?;
-            dataset.cameras.par_sort_unstable_by(|_, a, _, b| {
-                a.image
-                    .image_file_path
-                    .file_stem()
-                    .cmp(&b.image.image_file_path.file_stem())
-            });
-            dataset.points.par_sort_unstable_by(|a, b| {
-                a.position.partial_cmp(&b.position).expect("NaN")
-            });
-
-            let test_ids = dataset
-                .cameras
-                .keys()
-                .copied()
-                .enumerate()
-                .filter_map(|(index, id)| {
-                    (arguments.eval && index % 8 == 0).then_some((id, ()))
-                })
-                .collect::<IndexSet<_>>();
-
-            (dataset, test_ids)
+
+            (cameras, images, points)
         },
+        Nerfstudio => Transforms::read(&arguments.source_path)?
+            .into_colmap(&arguments.source_path)?,
     };
+    let transform = get_transform(&images);
+    if let Some(transform) = &transform {
+        transform.transform_images(&mut images);
+        transform.transform_points(&mut points);
+    }
+
+    // NOTE: The image file names of Nerfstudio include the image sub-directories.
+    let images_directories = match arguments.source_type {
+        Colmap => BTreeSet::from([arguments.images.to_owned()]),
+        Nerfstudio => images
+            .values()
+            .map(|image| {
+                let directory = image.file_name.rsplit_once('/').map(|(d, _)| d);
+                PathBuf::from(directory.unwrap_or_default())
+            })
+            .collect(),
+    };
+    let mut images_file = Files::default();
+    for images_directory in images_directories {
+        let image_file_pattern = [
+            arguments.source_path.as_path(),
+            images_directory.as_path(),
+            "*.*".as_ref(),
+        ]
+        .iter()
+        .collect::<PathBuf>();
+        images_file.extend(Files::open(image_file_pattern)?);
+    }
+    let source = colmap::ColmapSource {
+        cameras,
+        images,
+        images_file,
+        points,
+    };
+
+    let mut dataset = SparseViewDataset::init_from_colmap(source)?;
+    dataset.cameras.par_sort_unstable_by(|_, a, _, b| {
+        a.image
+            .image_file_path
+            .file_stem()
+            .cmp(&b.image.image_file_path.file_stem())
+    });
+    dataset
+        .points
+        .par_sort_unstable_by(|a, b| a.position.partial_cmp(&b.position).expect("NaN"));
+
+    let test_ids = dataset
+        .cameras
+        .keys()
+        .copied()
+        .enumerate()
+        .filter_map(|(index, id)| (arguments.eval && index % 8 == 0).then_some((id, ())))
+        .collect::<IndexSet<_>>();
 
     let test_size = test_ids.len();
     let train_size = dataset.cameras.len().saturating_sub(test_size);
@@ -109,7 +193,138 @@ pub fn get_cameras_and_points(
         },
     );
 
-    Ok((cameras_test, cameras_train, dataset.points))
+    Ok((cameras_test, cameras_train, dataset.points, transform))
+}
+
+/// Return the maximum saving iteration in the `model_directory`.
+///
+/// The `model_directory` includes the sub-directories of `iteration_*`.
+pub fn get_iteration_max(model_directory: impl AsRef<Path>) -> Result<u64, Report> {
+    let model_directory = model_directory.as_ref();
+    fs::read_dir(model_directory)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            path.file_name()?
+                .to_str()?
+                .split('_')
+                .next_back()?
+                .parse::<u64>()
+                .ok()
+        })
+        .max()
+        .ok_or_else(|| eyre!("Unrecognizable model directory: {model_directory:?}"))
+}
+
+/// Return the best saving iteration recorded in the `model_directory`.
+///
+/// It is `None` if no best iteration is recorded.
+pub fn get_iteration_best(
+    model_directory: impl AsRef<Path>
+) -> Result<Option<u64>, Report> {
+    let file_path = model_directory.as_ref().join(ITERATION_BEST_FILE_NAME);
+    match fs::read_to_string(&file_path) {
+        Ok(text) => Ok(Some(text.trim().parse::<u64>().map_err(|err| {
+            eyre!("Unrecognizable best iteration in {file_path:?}: {err}")
+        })?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Record the best saving `iteration` in the `model_directory`.
+///
+/// The record is removed if `iteration` is `None`.
+pub fn set_iteration_best(
+    model_directory: impl AsRef<Path>,
+    iteration: Option<u64>,
+) -> Result<(), Report> {
+    let file_path = model_directory.as_ref().join(ITERATION_BEST_FILE_NAME);
+    match iteration {
+        Some(iteration) => {
+            let mut file = File::open(file_path)?;
+            file.truncate()?;
+            file.write_all(format!("{iteration}\n").as_ref())?;
+        },
+        None => match fs::remove_file(file_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            _ => (),
+        },
+    }
+
+    Ok(())
+}
+
+/// Return the training time in seconds recorded in the `model_path`.
+pub fn get_time_train(model_path: impl AsRef<Path>) -> Result<Option<f64>, Report> {
+    let file_path = model_path.as_ref().join(TIME_TRAIN_FILE_NAME);
+    match fs::read_to_string(&file_path) {
+        Ok(text) => Ok(Some(text.trim().parse::<f64>().map_err(|err| {
+            eyre!("Unrecognizable training time in {file_path:?}: {err}")
+        })?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Record the training `time` in seconds in the `model_path`.
+///
+/// The record is removed if `time` is `None`.
+pub fn set_time_train(
+    model_path: impl AsRef<Path>,
+    time: Option<f64>,
+) -> Result<(), Report> {
+    let file_path = model_path.as_ref().join(TIME_TRAIN_FILE_NAME);
+    match time {
+        Some(time) => {
+            let mut file = File::open(file_path)?;
+            file.truncate()?;
+            file.write_all(format!("{time}\n").as_ref())?;
+        },
+        None => match fs::remove_file(file_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            _ => (),
+        },
+    }
+
+    Ok(())
+}
+
+/// Return the default saving iteration in the `model_directory`.
+///
+/// It is the best iteration if recorded, otherwise the maximum saving iteration.
+pub fn get_iteration_default(model_directory: impl AsRef<Path>) -> Result<u64, Report> {
+    let model_directory = model_directory.as_ref();
+    match get_iteration_best(model_directory)? {
+        Some(iteration) => Ok(iteration),
+        None => get_iteration_max(model_directory),
+    }
+}
+
+/// ## Returns
+///
+/// `(iteration, model_file_path)` in the `model_path`.
+///
+/// If `iteration` is not specified, it is the default saving iteration,
+/// i.e., the best iteration if recorded, otherwise the maximum saving iteration.
+pub fn get_model_file_path(
+    model_path: impl AsRef<Path>,
+    iteration: Option<u64>,
+) -> Result<(u64, PathBuf), Report> {
+    let model_directory = model_path.as_ref().join("point_cloud");
+    let iteration = match iteration {
+        Some(iteration) => iteration,
+        None => get_iteration_default(&model_directory)?,
+    };
+    let model_file_path = [
+        model_directory.as_path(),
+        format!("iteration_{iteration}").as_ref(),
+        "point_cloud.ply".as_ref(),
+    ]
+    .iter()
+    .collect::<PathBuf>();
+
+    Ok((iteration, model_file_path))
 }
 
 /// Return the M-SSIM and PSNR metrics.
@@ -161,6 +376,80 @@ pub fn get_mssim_and_psnr(
     Ok((mssim_mean, psnr_mean))
 }
 
+/// ## Returns
+///
+/// `(center, radius)` of the camera positions.
+///
+/// The radius is slightly enlarged from the farthest distance to the center.
+pub fn get_center_and_radius(cameras: &Cameras) -> ([f64; 3], f64) {
+    let count = cameras.len().max(1) as f64;
+    let center = cameras.values().fold([0.0; 3], |mut center, camera| {
+        center
+            .iter_mut()
+            .zip(camera.view.view_position)
+            .for_each(|(c, p)| *c += p / count);
+        center
+    });
+    let radius = cameras
+        .values()
+        .map(|camera| {
+            center
+                .iter()
+                .zip(camera.view.view_position)
+                .map(|(c, p)| (p - c).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        })
+        .fold(0.0, f64::max)
+        * 1.1;
+
+    (center, radius)
+}
+
+/// ## Returns
+///
+/// `(bound_min, bound_max)` of the camera frustums truncated at the `depth`.
+///
+/// The view transform is in column-major order,
+/// so the camera axes in world space are the rows of its rotation.
+pub fn get_frustum_bounds(
+    cameras: &Cameras,
+    depth: f64,
+) -> ([f64; 3], [f64; 3]) {
+    cameras.values().fold(
+        ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]),
+        |(mut bound_min, mut bound_max), camera| {
+            let view = &camera.view;
+            let transform = view.view_transform;
+            let axes = [0, 1, 2].map(|k| [0, 1, 2].map(|i| transform[i][k]));
+            let tan_x = (view.field_of_view_x / 2.0).tan();
+            let tan_y = (view.field_of_view_y / 2.0).tan();
+
+            let corners = [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)].map(
+                |(sign_x, sign_y)| {
+                    [0, 1, 2].map(|i| {
+                        view.view_position[i]
+                            + depth
+                                * (axes[2][i]
+                                    + sign_x * tan_x * axes[0][i]
+                                    + sign_y * tan_y * axes[1][i])
+                    })
+                },
+            );
+            iter::once(view.view_position)
+                .chain(corners)
+                .for_each(|point| {
+                    (0..3).for_each(|i| {
+                        bound_min[i] = bound_min[i].min(point[i]);
+                        bound_max[i] = bound_max[i].max(point[i]);
+                    })
+                });
+
+            (bound_min, bound_max)
+        },
+    )
+}
+
 /// Resize `cameras` to proper sizes.
 pub fn resize_cameras(cameras: &mut Cameras) -> Result<(), Report> {
     const IMAGE_SIZE_MIN: u32 = 320;
@@ -175,3 +464,28 @@ pub fn resize_cameras(cameras: &mut Cameras) -> Result<(), Report> {
         Ok(())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_iteration_default_with_best() {
+        let directory = std::env::temp_dir()
+            .join(format!("gausplat-scepter-iteration-{}", std::process::id()));
+        ["iteration_1000", "iteration_3000", "iteration_7000"]
+            .into_iter()
+            .for_each(|name| fs::create_dir_all(directory.join(name)).unwrap());
+
+        let output_max = get_iteration_default(&directory).unwrap();
+        set_iteration_best(&directory, Some(3000)).unwrap();
+        let output_best = get_iteration_default(&directory).unwrap();
+        set_iteration_best(&directory, None).unwrap();
+        let output_removed = get_iteration_best(&directory).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(output_max, 7000);
+        assert_eq!(output_best, 3000);
+        assert_eq!(output_removed, None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Pipeline runner for 3DGS.
+
+pub use super::*;
+pub use command::gaussian_3d::PipelineArguments;
+pub use train::TrainRunner;
+
+use render::RenderRunner;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::PathBuf, time::Instant};
+
+/// File name of the pipeline summary in the model directory.
+pub const SUMMARY_FILE_NAME: &str = "summary.json";
+
+/// Pipeline runner.
+#[derive(Clone)]
+pub struct PipelineRunner {
+    /// Arguments for the pipeline.
+    pub arguments: PipelineArguments,
+    /// Runner for training.
+    ///
+    /// Its cameras are shared by rendering and evaluation.
+    pub runner_train: TrainRunner,
+}
+
+/// Summary of the pipeline.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PipelineSummary {
+    /// The best iteration if recorded, otherwise the final iteration.
+    pub iteration: u64,
+    /// Mean structural similarity index on the testing dataset.
+    ///
+    /// It is `None` if there are no cameras for testing.
+    #[serde(rename = "SSIM")]
+    pub ssim: Option<f64>,
+    /// Peak signal-to-noise ratio on the testing dataset in dB.
+    ///
+    /// It is `None` if there are no cameras for testing.
+    #[serde(rename = "PSNR")]
+    pub psnr: Option<f64>,
+    /// Learned perceptual image patch similarity on the testing dataset.
+    ///
+    /// It is `None` since the metric is unavailable.
+    #[serde(rename = "LPIPS")]
+    pub lpips: Option<f64>,
+    /// Training time in seconds.
+    pub time_train: f64,
+    /// Number of Gaussians.
+    pub point_count: usize,
+    /// Size of the model file in bytes.
+    pub model_size: u64,
+    /// Model file path.
+    pub model_file_path: PathBuf,
+}
+
+impl PipelineArguments {
+    /// Initialize the pipeline runner.
+    pub fn init(&self) -> Result<PipelineRunner, Report> {
+        let arguments = self.to_owned();
+
+        // NOTE: The dataset is decoded only once.
+        let runner_train = self.train_arguments.init()?;
+
+        Ok(PipelineRunner {
+            arguments,
+            runner_train,
+        })
+    }
+}
+
+impl Runner for PipelineRunner {
+    fn run(mut self) -> Result<(), Report> {
+        // Specifying the parameters
+
+        let model_path = self
+            .arguments
+            .train_arguments
+            .common_arguments
+            .model_path
+            .to_owned();
+        let quiet = self.arguments.train_arguments.common_arguments.quiet;
+
+        // Training the scene
+
+        let time = Instant::now();
+        self.runner_train.train()?;
+        let time_train = time.elapsed().as_secs_f64();
+
+        // Loading the model at the best iteration or saving it at the final iteration
+
+        let runner = self.runner_train;
+        let (iteration, scene, model_file_path) =
+            match get_iteration_best(model_path.join("point_cloud"))? {
+                Some(iteration) => {
+                    let (iteration, model_file_path) =
+                        get_model_file_path(&model_path, Some(iteration))?;
+                    let scene = Gaussian3dScene::decode_polygon(
+                        &mut File::open(&model_file_path)?,
+                        &runner.scene.device(),
+                    )?;
+                    (iteration, scene, model_file_path)
+                },
+                None => {
+                    let iteration = runner.trainer.iteration;
+                    let scene = runner.scene.valid();
+                    let model_file_path =
+                        TrainRunner::save_model(iteration, &model_path, &scene)?;
+                    (iteration, scene, model_file_path)
+                },
+            };
+        let model_size = fs::metadata(&model_file_path)?.len();
+
+        // Rendering the testing dataset
+
+        if !self.arguments.skip_render {
+            let directory_test = [
+                model_path.as_path(),
+                "test".as_ref(),
+                format!("ours_{iteration}").as_ref(),
+            ]
+            .into_iter()
+            .collect::<PathBuf>();
+            let dir_test_rendered =
+                RenderRunner::make_directory(directory_test.join("renders"))?;
+            let dir_test_true = RenderRunner::make_directory(directory_test.join("gt"))?;
+
+            let mut bar = get_bar();
+            bar.colour = Some("ansi(190)".into());
+            bar.desc = "| Printing 3DGS".into();
+            bar.disable = quiet != 0;
+            bar.mininterval = 0.005;
+            bar.postfix = format!(" Iteration {iteration} |");
+
+            RenderRunner::save_images_rendered_and_true(
+                &mut bar,
+                runner.cameras_test.to_owned(),
+                &dir_test_rendered,
+                &dir_test_true,
+                &runner.trainer.options_renderer,
+                &scene,
+            )?;
+        }
+
+        // Evaluating the testing dataset
+
+        let (ssim, psnr) = if runner.cameras_test.is_empty() {
+            (None, None)
+        } else {
+            let (ssim, psnr) = get_mssim_and_psnr(
+                &runner.cameras_test,
+                &runner.trainer.options_renderer,
+                &scene,
+            )?;
+            (Some(ssim), Some(psnr))
+        };
+
+        // Saving the summary
+
+        let summary = PipelineSummary {
+            iteration,
+            ssim,
+            psnr,
+            lpips: None,
+            time_train,
+            point_count: scene.point_count(),
+            model_size,
+            model_file_path,
+        };
+        let summary_file_path = model_path.join(SUMMARY_FILE_NAME);
+        serde_json::to_writer_pretty(
+            File::open(&summary_file_path)?.truncate()?,
+            &summary,
+        )?;
+
+        // NOTE: The standard output is left to the JSON progress events.
+        if quiet < 3 {
+            eprintln!("{}", serde_json::to_string(&summary)?);
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PipelineRunner {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("PipelineRunner")
+            .field("arguments", &self.arguments)
+            .field("runner_train", &self.runner_train)
+            .finish()
+    }
+}
@@ -4,10 +4,11 @@ pub use super::*;
 pub use command::gaussian_3d::RenderArguments;
 pub use gausplat::loader::source::image::Image;
 
-use color_eyre::eyre::eyre;
+use progress::{ProgressEvent, ProgressWriter};
 use std::{
     fmt, fs,
     path::{Path, PathBuf},
+    time::Instant,
 };
 
 /// Rendering runner.
@@ -32,38 +33,12 @@ impl RenderArguments {
 
         // Loading the cameras and points
 
-        let (cameras_test, cameras_train, _) =
-            get_cameras_and_points(&self.common_arguments)?;
-
-        let model_directory = self.common_arguments.model_path.join("point_cloud");
-        let iteration = match self.iteration {
-            Some(iteration) => iteration,
-            None => {
-                // Finding the maximum iteration
-                fs::read_dir(&model_directory)?
-                    .filter_map(|entry| Some(entry.ok()?.path()))
-                    .filter(|path| path.is_dir())
-                    .filter_map(|path| {
-                        path.file_name()?
-                            .to_str()?
-                            .split('_')
-                            .next_back()?
-                            .parse::<u64>()
-                            .ok()
-                    })
-                    .max()
-                    .ok_or_else(|| {
-                        eyre!("Unrecognizable model directory: {model_directory:?}")
-                    })?
-            },
-        };
-        let model_file_path = [
-            model_directory.as_path(),
-            format!("iteration_{iteration}").as_ref(),
-            "point_cloud.ply".as_ref(),
-        ]
-        .iter()
-        .collect::<PathBuf>();
+        // NOTE: The cameras are in the frame of the model.
+        let (cameras_test, cameras_train, _, _) =
+            get_cameras_and_points_of_model(&self.common_arguments)?;
+
+        let (iteration, model_file_path) =
+            get_model_file_path(&self.common_arguments.model_path, self.iteration)?;
 
         // Loading the scene
 
@@ -186,7 +161,7 @@ impl Runner for RenderRunner {
             self.cameras_train.clear();
         }
 
-        // Specifying the progress bar
+        // Specifying the progress bar and events
 
         let mut bar = get_bar();
         bar.colour = Some("ansi(190)".into());
@@ -195,26 +170,57 @@ impl Runner for RenderRunner {
         bar.mininterval = 0.005;
         bar.postfix = format!(" Iteration {iteration} |");
 
+        let mut writer = ProgressWriter::init(&self.arguments.common_arguments)?;
+        if let Some(writer) = writer.as_mut() {
+            bar.disable = true;
+            writer.write(ProgressEvent::Start {
+                task: "render".into(),
+                total: (self.cameras_test.len() + self.cameras_train.len()) as u64,
+            })?;
+        }
+
         // Saving the rendered and true images
 
-        Self::save_images_rendered_and_true(
-            &mut bar,
-            self.cameras_test,
-            &dir_test_rendered,
-            &dir_test_true,
-            &options_renderer,
-            &self.scene,
-        )?;
-        Self::save_images_rendered_and_true(
-            &mut bar,
-            self.cameras_train,
-            &dir_train_rendered,
-            &dir_train_true,
-            &options_renderer,
-            &self.scene,
-        )?;
+        let time = Instant::now();
+        let result: Result<(), Report> = [
+            (self.cameras_test, dir_test_rendered),
+            (self.cameras_train, dir_train_rendered),
+        ]
+        .into_iter()
+        .zip([dir_test_true, dir_train_true])
+        .try_for_each(|((cameras, directory_rendered), directory_true)| {
+            if cameras.is_empty() {
+                return Ok(());
+            }
+            Self::save_images_rendered_and_true(
+                &mut bar,
+                cameras,
+                &directory_rendered,
+                &directory_true,
+                &options_renderer,
+                &self.scene,
+            )?;
+            if let Some(writer) = writer.as_mut() {
+                writer.write(ProgressEvent::Save {
+                    iteration,
+                    path: directory_rendered,
+                })?;
+            }
+            Ok(())
+        });
+
+        if let Some(writer) = writer.as_mut() {
+            writer.write(match &result {
+                Ok(()) => ProgressEvent::Complete {
+                    elapsed: time.elapsed().as_secs_f64(),
+                },
+                Err(err) => ProgressEvent::Error {
+                    message: err.to_string(),
+                },
+            })?;
+        }
 
-        Ok(())
+        result
     }
 }
 
@@ -0,0 +1,157 @@
+//! Learning rate schedules for 3DGS.
+
+pub use super::*;
+pub use command::gaussian_3d::LearningRateSchedule;
+
+use std::f64::consts::PI;
+
+/// Scheduler of a learning rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LearningRateScheduler {
+    /// Schedule of the learning rate.
+    pub schedule: LearningRateSchedule,
+    /// Learning rate at initial.
+    pub start: f64,
+    /// Learning rate at final.
+    pub end: f64,
+    /// Number of iterations from the initial to the final learning rate.
+    pub count: u64,
+    /// Number of warm-up iterations.
+    pub warmup: u64,
+    /// Iterations for decaying by the `gamma`.
+    pub milestones: Vec<u64>,
+    /// Decaying factor at each milestone.
+    pub gamma: f64,
+}
+
+impl LearningRateScheduler {
+    /// Return the learning rate at the `iteration`.
+    ///
+    /// The `iteration` starts from one.
+    pub fn get(
+        &self,
+        iteration: u64,
+    ) -> f64 {
+        let Self {
+            start,
+            end,
+            count,
+            warmup,
+            ..
+        } = *self;
+
+        match self.schedule {
+            LearningRateSchedule::Exponential => {
+                let progress = iteration.min(count) as f64 / count.max(1) as f64;
+                start * (end / start).powf(progress)
+            },
+            LearningRateSchedule::Cosine | LearningRateSchedule::Linear => {
+                if iteration <= warmup {
+                    return start * iteration as f64 / warmup as f64;
+                }
+
+                let progress = ((iteration - warmup) as f64
+                    / count.saturating_sub(warmup).max(1) as f64)
+                    .min(1.0);
+                let factor = match self.schedule {
+                    LearningRateSchedule::Cosine => (1.0 + (PI * progress).cos()) / 2.0,
+                    _ => 1.0 - progress,
+                };
+                end + (start - end) * factor
+            },
+            LearningRateSchedule::Step => {
+                let count = self
+                    .milestones
+                    .iter()
+                    .filter(|&&milestone| milestone <= iteration)
+                    .count();
+                start * self.gamma.powi(count as i32)
+            },
+            LearningRateSchedule::Constant => start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(
+        output: f64,
+        target: f64,
+    ) {
+        assert!((output - target).abs() < 1e-12, "{output} != {target}");
+    }
+
+    fn get_scheduler(schedule: LearningRateSchedule) -> LearningRateScheduler {
+        LearningRateScheduler {
+            schedule,
+            start: 1e-2,
+            end: 1e-4,
+            count: 1000,
+            warmup: 100,
+            milestones: vec![300, 600],
+            gamma: 0.1,
+        }
+    }
+
+    #[test]
+    fn exponential() {
+        let scheduler = get_scheduler(LearningRateSchedule::Exponential);
+
+        assert_close(scheduler.get(0), 1e-2);
+        assert_close(scheduler.get(500), 1e-3);
+        assert_close(scheduler.get(1000), 1e-4);
+        assert_close(scheduler.get(2000), 1e-4);
+    }
+
+    #[test]
+    fn cosine() {
+        let scheduler = get_scheduler(LearningRateSchedule::Cosine);
+
+        assert_close(scheduler.get(50), 5e-3);
+        assert_close(scheduler.get(100), 1e-2);
+        assert_close(scheduler.get(550), (1e-2 + 1e-4) / 2.0);
+        assert_close(scheduler.get(1000), 1e-4);
+        assert_close(scheduler.get(2000), 1e-4);
+    }
+
+    #[test]
+    fn linear() {
+        let scheduler = get_scheduler(LearningRateSchedule::Linear);
+
+        assert_close(scheduler.get(1), 1e-4);
+        assert_close(scheduler.get(100), 1e-2);
+        assert_close(scheduler.get(550), (1e-2 + 1e-4) / 2.0);
+        assert_close(scheduler.get(1000), 1e-4);
+    }
+
+    #[test]
+    fn linear_without_warmup() {
+        let scheduler = LearningRateScheduler {
+            warmup: 0,
+            ..get_scheduler(LearningRateSchedule::Linear)
+        };
+
+        assert_close(scheduler.get(1), 1e-2 - 9.9e-3 / 1000.0);
+        assert_close(scheduler.get(1000), 1e-4);
+    }
+
+    #[test]
+    fn step() {
+        let scheduler = get_scheduler(LearningRateSchedule::Step);
+
+        assert_close(scheduler.get(299), 1e-2);
+        assert_close(scheduler.get(300), 1e-3);
+        assert_close(scheduler.get(600), 1e-4);
+        assert_close(scheduler.get(2000), 1e-4);
+    }
+
+    #[test]
+    fn constant() {
+        let scheduler = get_scheduler(LearningRateSchedule::Constant);
+
+        assert_close(scheduler.get(1), 1e-2);
+        assert_close(scheduler.get(2000), 1e-2);
+    }
+}
@@ -1,24 +1,59 @@
 //! Training runner for 3DGS.
 
 pub use super::*;
-pub use command::gaussian_3d::TrainArguments;
+pub use callback::{TrainCallback, TrainContext, TrainLoss};
+pub use command::gaussian_3d::{
+    CameraSampling, EarlyStoppingMetric, InitKind, InitScaleRule, InitStrategy,
+    TrainArguments,
+};
 pub use gausplat::trainer::train::gaussian_3d::Gaussian3dTrainerConfig;
+pub use schedule::{LearningRateSchedule, LearningRateScheduler};
 
+use budget::PointBudget;
+use burn::tensor::{backend::Backend, TensorData};
+use callback::{
+    call_hooks, DurationCallback, EarlyStoppingCallback, JsonProgressCallback,
+    ProgressCallback, SaveCallback, TestCallback,
+};
+use color_eyre::eyre::eyre;
+use command::gaussian_3d::INIT_POINT_COUNT_DEFAULT;
+use gausplat::loader::collection::IndexMap;
 use gausplat::renderer::spherical_harmonics::SH_DEGREE_MAX;
 use gausplat::trainer::{
-    metric::{Metric, Psnr},
+    metric::MeanStructuralSimilarity,
     optimize::LearningRateConfig,
     range::RangeOptions,
-    train::gaussian_3d::{
-        Autodiff, AutodiffModule, Gaussian3dTrainer, RefinerConfig, SEED,
-    },
+    train::gaussian_3d::{Autodiff, AutodiffModule, Gaussian3dTrainer, RefinerConfig},
+};
+use progress::{ProgressFormat, ProgressWriter};
+use rand::{
+    rngs::StdRng,
+    seq::{index, SliceRandom},
+    Rng, SeedableRng,
 };
+use runner::dataset::transform::SceneTransform;
 use std::{
-    fmt, fs,
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap},
+    error::Error,
+    f64::consts::PI,
+    fmt, fs, iter,
+    ops::Range,
     path::{Path, PathBuf},
+    rc::Rc,
     time::Instant,
 };
 
+/// The training is stopped before the final iteration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stopped;
+
+/// Ratio of the sphere shell radius to the scene radius at initialization.
+pub const INIT_SPHERE_RADIUS_RATIO: f64 = 10.0;
+
+/// File name of the model in the dataset frame if the scene is normalized.
+pub const MODEL_FILE_NAME_DATASET_FRAME: &str = "point_cloud_dataset.ply";
+
 /// Training runner.
 #[derive(Clone)]
 pub struct TrainRunner {
@@ -39,15 +74,64 @@ impl TrainArguments {
     pub fn init(&self) -> Result<TrainRunner, Report> {
         let arguments = self.to_owned();
 
+        // Checking the arguments
+
+        if self.resolution_factors.iter().any(|factor| *factor == 0) {
+            return Err(eyre!("The resolution factors should be positive"));
+        }
+        if self.resolution_factors.len() != self.resolution_iterations.len() + 1 {
+            return Err(eyre!(
+                "The resolution iterations should have one less value than the factors"
+            ));
+        }
+
+        if self.early_stopping_interval.is_some() && !self.common_arguments.eval {
+            return Err(eyre!("The early stopping requires the evaluation mode"));
+        }
+        if self.early_stopping_interval == Some(0) {
+            return Err(eyre!("The early stopping interval should be positive"));
+        }
+
+        if self
+            .max_duration
+            .is_some_and(|duration| !(duration.is_finite() && duration > 0.0))
+        {
+            return Err(eyre!("The maximum duration should be positive and finite"));
+        }
+
         // Loading the cameras and points
 
-        let (cameras_test, cameras_train, points) =
+        let (cameras_test, cameras_train, points, transform) =
             get_cameras_and_points(&self.common_arguments)?;
 
+        // Saving the scene transform
+
+        // NOTE: The stale transform of the previous training is removed.
+        let model_path = &self.common_arguments.model_path;
+        match transform {
+            Some(transform) => {
+                transform.save(model_path)?;
+            },
+            None => SceneTransform::remove(model_path)?,
+        }
+
+        // NOTE: The stale records of the previous training are removed.
+        set_iteration_best(model_path.join("point_cloud"), None)?;
+        set_time_train(model_path, None)?;
+
         // Initializing the scene and trainer
 
+        // NOTE: The randomness of the trainer, e.g., the densification noise,
+        // is drawn from the backend.
         let device = WgpuDevice::default();
-        let scene = Gaussian3dScene::from_points(points, &device);
+        <Autodiff<Wgpu> as Backend>::seed(self.seed);
+        let scene = TrainRunner::init_scene(
+            &self.init,
+            self.seed,
+            &cameras_train,
+            points,
+            &device,
+        )?;
         let trainer = Gaussian3dTrainerConfig::from(self).init(&device);
 
         Ok(TrainRunner {
@@ -60,7 +144,215 @@ impl TrainArguments {
     }
 }
 
+impl TrainArguments {
+    /// Return the learning rate schedulers other than the exponential ones.
+    ///
+    /// ## Returns
+    ///
+    /// The schedulers of color SH feature, opacity, position, rotation, and scaling.
+    pub fn get_learning_rate_schedulers(&self) -> [Option<LearningRateScheduler>; 5] {
+        let scheduler = |schedule: LearningRateSchedule,
+                         start: f64,
+                         end: Option<f64>,
+                         count: Option<u64>| {
+            (schedule != LearningRateSchedule::Exponential).then(|| {
+                LearningRateScheduler {
+                    schedule,
+                    start,
+                    end: end.unwrap_or(start),
+                    count: count.unwrap_or(self.iterations),
+                    warmup: self.lr_warmup_steps,
+                    milestones: self.lr_milestones.to_owned(),
+                    gamma: self.lr_gamma,
+                }
+            })
+        };
+
+        [
+            scheduler(
+                self.feature_lr_schedule,
+                self.feature_lr,
+                self.feature_lr_final,
+                self.feature_lr_max_steps,
+            ),
+            scheduler(
+                self.opacity_lr_schedule,
+                self.opacity_lr,
+                self.opacity_lr_final,
+                self.opacity_lr_max_steps,
+            ),
+            scheduler(
+                self.position_lr_schedule,
+                self.position_lr_init,
+                Some(self.position_lr_final),
+                Some(self.position_lr_max_steps),
+            ),
+            scheduler(
+                self.rotation_lr_schedule,
+                self.rotation_lr,
+                self.rotation_lr_final,
+                self.rotation_lr_max_steps,
+            ),
+            scheduler(
+                self.scaling_lr_schedule,
+                self.scaling_lr,
+                self.scaling_lr_final,
+                self.scaling_lr_max_steps,
+            ),
+        ]
+    }
+}
+
 impl TrainRunner {
+    /// Initialize the scene with the union of points from the `strategies`.
+    pub fn init_scene(
+        strategies: &[InitStrategy],
+        seed: u64,
+        cameras: &Cameras,
+        points: Points,
+        device: &WgpuDevice,
+    ) -> Result<Gaussian3dScene<Autodiff<Wgpu>>, Report> {
+        let (points_init, ranges) =
+            Self::get_points_init(strategies, seed, cameras, &points)?;
+
+        // Initializing the scene
+
+        let point_count = points_init.len();
+        let mut scene = Gaussian3dScene::from_points(points_init, device);
+
+        // Overriding the opacities and scalings
+
+        if ranges.iter().any(|(_, s)| s.opacity.is_some()) {
+            let mut opacities = Self::get_values(scene.opacities());
+            Self::set_values(&mut opacities, 1, &ranges, |s| s.opacity);
+            scene.set_opacities(Tensor::from_data(
+                TensorData::new(opacities, [point_count, 1]),
+                device,
+            ));
+        }
+        if ranges
+            .iter()
+            .any(|(_, s)| matches!(s.scale, InitScaleRule::Constant(_)))
+        {
+            let mut scalings = Self::get_values(scene.scalings());
+            Self::set_values(&mut scalings, 3, &ranges, |s| match s.scale {
+                InitScaleRule::Constant(scale) => Some(scale),
+                InitScaleRule::Neighbor => None,
+            });
+            scene.set_scalings(Tensor::from_data(
+                TensorData::new(scalings, [point_count, 3]),
+                device,
+            ));
+        }
+
+        Ok(scene)
+    }
+
+    /// Sample the points from the `strategies`.
+    ///
+    /// The points are identical for the same `seed`.
+    ///
+    /// ## Returns
+    ///
+    /// `(points_init, ranges)`, where `ranges` are the point ranges of each strategy.
+    pub fn get_points_init<'s>(
+        strategies: &'s [InitStrategy],
+        seed: u64,
+        cameras: &Cameras,
+        points: &Points,
+    ) -> Result<(Points, Vec<(Range<usize>, &'s InitStrategy)>), Report> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (center, radius) = get_center_and_radius(cameras);
+
+        // Sampling the points
+
+        let mut points_init = Points::new();
+        let mut ranges = Vec::with_capacity(strategies.len());
+        for strategy in strategies {
+            let start = points_init.len();
+            let count = strategy.count.unwrap_or(INIT_POINT_COUNT_DEFAULT) as usize;
+
+            match strategy.kind {
+                InitKind::Sfm => match strategy.count {
+                    Some(count) if (count as usize) < points.len() => {
+                        let mut indices =
+                            index::sample(&mut rng, points.len(), count as usize)
+                                .into_vec();
+                        indices.sort_unstable();
+                        points_init
+                            .extend(indices.into_iter().map(|i| points[i].to_owned()));
+                    },
+                    _ => points_init.extend_from_slice(points),
+                },
+                InitKind::Box => {
+                    if cameras.is_empty() {
+                        return Err(eyre!(
+                            "The box initialization requires the training cameras"
+                        ));
+                    }
+                    // NOTE: The frustums are truncated at the scene radius.
+                    let (bound_min, bound_max) = get_frustum_bounds(cameras, radius);
+                    points_init.extend((0..count).map(|_| Point {
+                        color_rgb: rng.gen(),
+                        position:
+                            [0, 1, 2].map(|i| rng.gen_range(bound_min[i]..=bound_max[i])),
+                    }));
+                },
+                InitKind::Sphere => {
+                    let radius_shell = radius * INIT_SPHERE_RADIUS_RATIO;
+                    points_init.extend((0..count).map(|_| {
+                        let z = rng.gen_range(-1.0..=1.0_f64);
+                        let phi = rng.gen_range(0.0..2.0 * PI);
+                        let r = (1.0 - z * z).sqrt();
+                        let direction = [r * phi.cos(), r * phi.sin(), z];
+                        Point {
+                            color_rgb: rng.gen(),
+                            position: [0, 1, 2]
+                                .map(|i| center[i] + radius_shell * direction[i]),
+                        }
+                    }));
+                },
+            }
+
+            log::info!(
+                target: "gausplat::scepter::gaussian_3d::train",
+                "init {} points with '{strategy}'", points_init.len() - start,
+            );
+            ranges.push((start..points_init.len(), strategy));
+        }
+
+        if points_init.is_empty() {
+            return Err(eyre!("No point is available for initialization"));
+        }
+
+        Ok((points_init, ranges))
+    }
+
+    /// Return the values of the `tensor`.
+    #[inline]
+    fn get_values(tensor: Tensor<Autodiff<Wgpu>, 2>) -> Vec<f64> {
+        // NOTE: The data type is converted.
+        tensor
+            .into_data()
+            .convert::<f64>()
+            .into_vec::<f64>()
+            .unwrap()
+    }
+
+    /// Overwrite the `values` of the point ranges with the specified value.
+    fn set_values(
+        values: &mut [f64],
+        width: usize,
+        ranges: &[(Range<usize>, &InitStrategy)],
+        value_of: impl Fn(&InitStrategy) -> Option<f64>,
+    ) {
+        ranges.iter().for_each(|(range, strategy)| {
+            if let Some(value) = value_of(strategy) {
+                values[range.start * width..range.end * width].fill(value);
+            }
+        });
+    }
+
     /// Saves the model to the specified path.
     pub fn save_model(
         iteration: u64,
@@ -79,44 +371,189 @@ impl TrainRunner {
         let model_file_path = model_directory.join("point_cloud.ply");
         scene.encode_polygon(File::open(&model_file_path)?.truncate()?)?;
 
+        // Saving the model in the dataset frame
+
+        if model_path
+            .as_ref()
+            .join(SceneTransform::FILE_NAME)
+            .is_file()
+        {
+            let transform = SceneTransform::load(model_path)?.inverse();
+            let model_file_path = model_directory.join(MODEL_FILE_NAME_DATASET_FRAME);
+            Self::transform_scene(scene, &transform)?
+                .encode_polygon(File::open(&model_file_path)?.truncate()?)?;
+        }
+
+        Ok(model_file_path)
+    }
+
+    /// Return the scene transformed by the `transform`.
+    pub fn transform_scene(
+        scene: &Gaussian3dScene<Wgpu>,
+        transform: &SceneTransform,
+    ) -> Result<Gaussian3dScene<Wgpu>, Report> {
+        let get_values = |tensor: Tensor<Wgpu, 2>| {
+            tensor
+                .into_data()
+                .convert::<f64>()
+                .into_vec::<f64>()
+                .map_err(|err| eyre!("{err:?}"))
+        };
+
+        let device = scene.device();
+        let point_count = scene.point_count();
+        let mut positions = get_values(scene.positions())?;
+        let mut rotations = get_values(scene.rotations())?;
+        let mut scalings = get_values(scene.scalings())?;
+        let mut colors_sh = get_values(scene.colors_sh())?;
+        transform.transform_gaussians(
+            &mut positions,
+            &mut rotations,
+            &mut scalings,
+            &mut colors_sh,
+        );
+
+        let mut scene = scene.to_owned();
+        let colors_sh_width = colors_sh.len() / point_count.max(1);
+        scene.set_positions(Tensor::from_data(
+            TensorData::new(positions, [point_count, 3]),
+            &device,
+        ));
+        scene.set_rotations(Tensor::from_data(
+            TensorData::new(rotations, [point_count, 4]),
+            &device,
+        ));
+        scene.set_scalings(Tensor::from_data(
+            TensorData::new(scalings, [point_count, 3]),
+            &device,
+        ));
+        scene.set_colors_sh(Tensor::from_data(
+            TensorData::new(colors_sh, [point_count, colors_sh_width]),
+            &device,
+        ));
+
+        Ok(scene)
+    }
+
+    /// Saves the model and a marker noting the interruption.
+    pub fn save_model_interrupted(
+        iteration: u64,
+        iterations: u64,
+        model_path: impl AsRef<Path>,
+        scene: &Gaussian3dScene<Wgpu>,
+    ) -> Result<PathBuf, Report> {
+        let model_file_path = Self::save_model(iteration, model_path, scene)?;
+
+        // NOTE: The model directory is the parent of the model file.
+        let marker_file_path = model_file_path.with_file_name("interrupted.txt");
+        let mut marker_file = File::open(marker_file_path)?;
+        marker_file.truncate()?;
+        marker_file.write_all(
+            format!("Interrupted at iteration {iteration} of {iterations}\n").as_ref(),
+        )?;
+
         Ok(model_file_path)
     }
 }
 
-impl Runner for TrainRunner {
-    fn run(mut self) -> Result<(), Report> {
+impl TrainRunner {
+    /// Train the scene and keep it in the runner.
+    #[inline]
+    pub fn train(&mut self) -> Result<(), Report> {
+        self.train_with_callbacks(Vec::new())
+    }
+
+    /// Return the default callbacks specified by the arguments.
+    ///
+    /// The progress `bar` is disabled if the progress format is JSON.
+    pub fn get_callbacks(
+        &self,
+        bar: &Rc<RefCell<Bar>>,
+        device: &WgpuDevice,
+    ) -> Result<Vec<Box<dyn TrainCallback>>, Report> {
+        let quiet = self.arguments.common_arguments.quiet;
+        let writer = ProgressWriter::init(&self.arguments.common_arguments)?;
+        let can_show_details = quiet < 1 && writer.is_none();
+        let can_show_test = quiet < 2;
+        let can_show_save = quiet < 3;
+
+        let mut callbacks = Vec::<Box<dyn TrainCallback>>::new();
+        if let Some(writer) = writer {
+            bar.borrow_mut().disable = true;
+            let interval = self.arguments.common_arguments.progress_interval;
+            callbacks.push(Box::new(JsonProgressCallback::init(writer, interval)));
+        }
+        callbacks.push(Box::new(ProgressCallback::init(
+            bar.to_owned(),
+            can_show_details,
+            device,
+        )));
+        if can_show_test {
+            callbacks.push(Box::new(TestCallback::init(
+                &self.arguments.test_iterations,
+            )));
+        }
+        callbacks.push(Box::new(SaveCallback::init(
+            &self.arguments.save_iterations,
+            can_show_save,
+        )));
+        if let Some(interval) = self.arguments.early_stopping_interval {
+            callbacks.push(Box::new(EarlyStoppingCallback::init(
+                interval,
+                self.arguments.early_stopping_patience,
+                self.arguments.early_stopping_metric,
+            )));
+        }
+        if let Some(max_duration) = self.arguments.max_duration {
+            callbacks.push(Box::new(DurationCallback::init(
+                max_duration,
+                can_show_test,
+            )));
+        }
+        Ok(callbacks)
+    }
+
+    /// Train the scene with the default and `callbacks_extra`.
+    ///
+    /// The extra callbacks are called after the default ones.
+    pub fn train_with_callbacks(
+        &mut self,
+        mut callbacks_extra: Vec<Box<dyn TrainCallback>>,
+    ) -> Result<(), Report> {
         // Specifying the parameters
 
         let device = self.scene.device();
         let iterations = self.arguments.iterations as usize;
-
-        let mut iterations_test_reversed = self.arguments.test_iterations.to_owned();
-        iterations_test_reversed.sort_unstable_by(|a, b| b.cmp(a));
-        let mut iterations_save_reversed = self.arguments.save_iterations.to_owned();
-        iterations_save_reversed.sort_unstable_by(|a, b| b.cmp(a));
-
-        let metric_psnr = Psnr::init(&device);
-        let range_detail_update = self.trainer.refiner.config.range_densification;
+        let range_densification = self.trainer.refiner.config.range_densification;
+        let range_sh_degree_increase = self
+            .trainer
+            .refiner
+            .config
+            .range_increasing_colors_sh_degree_max;
         let quiet = self.arguments.common_arguments.quiet;
 
-        let can_show_details = quiet < 1;
-        let can_show_test = quiet < 2;
-        let can_show_save = quiet < 3;
         let can_show_size = quiet < 3;
+        // NOTE: The JSON progress events report the saving instead.
+        let can_show_save =
+            quiet < 3 && self.arguments.common_arguments.progress != ProgressFormat::Json;
 
         // Specifying the progress bar
 
-        let mut bar = get_bar();
-        let mut psnr = 0.0;
+        let bar = Rc::new(RefCell::new(get_bar()));
         let mut size = "0.0 B".to_string();
-        bar.colour = Some("ansi(41)".into());
-        bar.desc = "| Training 3DGS".into();
-        bar.disable = quiet > 1;
-        bar.total = iterations;
-        if can_show_details {
-            bar.postfix = format!(" {size} | PSNR {psnr:.2} dB |");
+        {
+            let mut bar = bar.borrow_mut();
+            bar.colour = Some("ansi(41)".into());
+            bar.desc = "| Training 3DGS".into();
+            bar.disable = quiet > 1;
+            bar.total = iterations;
         }
 
+        // Specifying the callbacks
+
+        let mut callbacks = self.get_callbacks(&bar, &device)?;
+        callbacks.append(&mut callbacks_extra);
+
         // Rescaling down the images at initialization
 
         let time = Instant::now();
@@ -126,86 +563,239 @@ impl Runner for TrainRunner {
             "may rescale in {:.03?}", time.elapsed(),
         );
 
-        // Optimizing the scene iteratively
+        // Specifying the resolution levels
+
+        // NOTE: The cameras are downscaled lazily and cached for the current level.
+        let factors = self.arguments.resolution_factors.to_owned();
+        let mut iterations_level = self.arguments.resolution_iterations.to_owned();
+        iterations_level.sort_unstable();
+        let mut cameras_level = HashMap::<usize, Camera>::new();
+        let threshold_position_2d_grad_norm =
+            self.trainer.refiner.config.threshold_position_2d_grad_norm;
+
+        // Scheduling the learning rates
+
+        let schedulers = self.arguments.get_learning_rate_schedulers();
+
+        // Sampling the cameras
+
+        let seed = self.arguments.seed;
+        let positions = (0..self.cameras_train.len())
+            .map(|position| (position, position))
+            .collect::<IndexMap<_, _>>();
+        let positions: Box<dyn Iterator<Item = usize>> =
+            match self.arguments.camera_sampling {
+                CameraSampling::Random => {
+                    Box::new(positions.seed(seed).random_values().copied())
+                },
+                CameraSampling::Epoch => {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let mut positions = positions.keys().copied().collect::<Vec<_>>();
+                    Box::new(
+                        iter::repeat_with(move || {
+                            positions.shuffle(&mut rng);
+                            positions.to_owned()
+                        })
+                        .take_while(|positions| !positions.is_empty())
+                        .flatten(),
+                    )
+                },
+            };
 
-        let result = self
-            .cameras_train
-            .seed(SEED)
-            .random_values()
-            .take(iterations)
-            .try_for_each(|camera| {
-                self.trainer.train(&mut self.scene, camera)?;
-                bar.update(1)?;
-
-                // Specifying the parameters
-                let iteration = self.trainer.iteration;
-                if can_show_size {
-                    size = self.scene.size_readable();
-                }
+        // Specifying the point budget
 
-                // Updating the progress details
-                if can_show_details && range_detail_update.has(iteration) {
-                    let scene = self.scene.valid();
-                    let output = scene
-                        .render(&camera.view, &self.trainer.options_renderer)?
-                        .colors_rgb_2d;
-                    let target = camera.image.decode_rgb_tensor(&device)?;
-                    psnr = metric_psnr.evaluate(output, target).into_scalar();
-                    bar.postfix = format!(" {size} | PSNR {psnr:.2} dB |");
-                    if !bar.disable {
-                        bar.refresh()?;
-                    }
-                }
+        let mut budget = PointBudget::init(
+            self.arguments.max_gaussians,
+            self.trainer.refiner.config.threshold_position_2d_grad_norm,
+        );
+        budget.update(&mut self.trainer.refiner.config, &self.scene, 0)?;
 
-                // NOTE: The progress should be shown only once.
-                let mut should_show_progress = true;
+        // Stopping gracefully at the interruption
 
-                // Testing the model
-                if can_show_test && Some(&iteration) == iterations_test_reversed.last() {
-                    iterations_test_reversed.pop();
+        // NOTE: The task is interruptible until the training ends.
+        let _interrupt_guard = init_interrupt_handler()?;
 
-                    let (mssim, psnr) = get_mssim_and_psnr(
-                        &self.cameras_test,
-                        &self.trainer.options_renderer,
-                        &self.scene.valid(),
-                    )?;
+        // Optimizing the scene iteratively
 
-                    if should_show_progress {
-                        should_show_progress = false;
-                        bar.refresh()?;
-                    }
-                    eprintln!(
-                        "|  Testing 3DGS | {size} | \
-                        PSNR {psnr:.2} dB | SSIM {mssim:.3} |"
-                    );
+        // NOTE: The metric is shared by the contexts.
+        let metric_mssim = MeanStructuralSimilarity::<Wgpu, 3>::init(&device);
+        let time = Instant::now();
+        call_hooks(&mut callbacks, |callback| {
+            callback.on_start(iterations as u64)
+        })?;
+        let mut level = usize::MAX;
+        let result = positions.take(iterations).try_for_each(|position| {
+            // Stepping up the resolution level
+            let iteration_next = self.trainer.iteration + 1;
+            let level_next = iterations_level
+                .partition_point(|&iteration| iteration <= iteration_next);
+            if level != level_next {
+                level = level_next;
+                cameras_level.clear();
+                let factor = factors[level];
+                // NOTE: A coarse pixel spans `factor` fine pixels.
+                budget.threshold = threshold_position_2d_grad_norm * factor as f64;
+                self.trainer.refiner.config.threshold_position_2d_grad_norm =
+                    budget.get_threshold(self.scene.point_count() as u64);
+                log::info!(
+                    target: "gausplat::scepter::gaussian_3d::train",
+                    "train at 1/{factor} resolution from iteration {iteration_next}",
+                );
+            }
+
+            // Scheduling the learning rates
+            let trainer = &mut self.trainer;
+            [
+                &mut trainer.learning_rate_colors_sh,
+                &mut trainer.learning_rate_opacities,
+                &mut trainer.learning_rate_positions,
+                &mut trainer.learning_rate_rotations,
+                &mut trainer.learning_rate_scalings,
+            ]
+            .into_iter()
+            .zip(&schedulers)
+            .for_each(|(learning_rate, scheduler)| {
+                if let Some(scheduler) = scheduler {
+                    learning_rate.current = scheduler.get(iteration_next);
                 }
+            });
 
-                // Saving the model
-                if Some(&iteration) == iterations_save_reversed.last() {
-                    iterations_save_reversed.pop();
-
-                    let model_path = &self.arguments.common_arguments.model_path;
-                    Self::save_model(iteration, model_path, &self.scene.valid())?;
-
-                    if can_show_save {
-                        if should_show_progress {
-                            bar.refresh()?;
-                        }
-                        eprintln!("|   Saving 3DGS | {size} |");
-                    }
+            // Downscaling the camera at the resolution level
+            // NOTE: The position is in bounds.
+            let camera = self.cameras_train.get_index(position).unwrap().1;
+            let factor = factors[level];
+            let camera: &Camera = if factor == 1 {
+                camera
+            } else {
+                match cameras_level.entry(position) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let mut camera = camera.to_owned();
+                        camera.resize_max((camera.size_max() / factor).max(1))?;
+                        entry.insert(camera)
+                    },
                 }
+            };
+            self.trainer.train(&mut self.scene, camera)?;
+
+            // Specifying the parameters
+            let iteration = self.trainer.iteration;
+            if can_show_size {
+                size = self.scene.size_readable();
+            }
+
+            // Updating the point budget after the refinement
+            if range_densification.has(iteration) {
+                budget.update(
+                    &mut self.trainer.refiner.config,
+                    &self.scene,
+                    iteration,
+                )?;
+            }
+
+            let context = TrainContext::init(
+                iteration,
+                camera,
+                &self.cameras_test,
+                &self.arguments.common_arguments.model_path,
+                &self.trainer.options_renderer,
+                &self.scene,
+                &size,
+                &metric_mssim,
+                &bar,
+            );
+
+            // Calling the hooks
+            call_hooks(&mut callbacks, |callback| callback.on_step(&context))?;
+            if range_densification.has(iteration) {
+                call_hooks(&mut callbacks, |callback| callback.on_densify(&context))?;
+            }
+            if range_sh_degree_increase.has(iteration) {
+                call_hooks(&mut callbacks, |callback| {
+                    callback.on_sh_degree_increase(&context)
+                })?;
+            }
+            // NOTE: The evaluation requires the cameras for testing.
+            if !self.cameras_test.is_empty()
+                && callbacks
+                    .iter()
+                    .any(|callback| callback.should_eval(iteration))
+            {
+                let (mssim, psnr) = get_mssim_and_psnr(
+                    &self.cameras_test,
+                    &self.trainer.options_renderer,
+                    &self.scene.valid(),
+                )?;
+                call_hooks(&mut callbacks, |callback| {
+                    callback.on_eval(&context, mssim, psnr)
+                })?;
+            }
+            if callbacks
+                .iter()
+                .any(|callback| callback.should_save(iteration))
+            {
+                let model_file_path =
+                    Self::save_model(iteration, context.model_path, &self.scene.valid())?;
+                call_hooks(&mut callbacks, |callback| {
+                    callback.on_save(&context, &model_file_path)
+                })?;
+            }
+
+            // Stopping at the interruption
+            if is_interrupted() {
+                return Err(Interrupted { iteration }.into());
+            }
+
+            Ok(())
+        });
+
+        if !bar.borrow().disable {
+            eprintln!();
+        }
 
-                Ok(())
-            });
+        // Saving the model at the interruption
+        if let Some(Interrupted { iteration }) =
+            result.as_ref().err().and_then(|err| err.downcast_ref())
+        {
+            let model_file_path = Self::save_model_interrupted(
+                *iteration,
+                self.arguments.iterations,
+                &self.arguments.common_arguments.model_path,
+                &self.scene.valid(),
+            )?;
+            if can_show_save {
+                eprintln!("|   Saving 3DGS | {size} | {model_file_path:?} |");
+            }
+            call_hooks(&mut callbacks, |callback| {
+                callback.on_save_interrupted(*iteration, &model_file_path)
+            })?;
+        }
 
-        if !bar.disable {
-            eprintln!();
+        let result = match result {
+            Err(err) if err.is::<Stopped>() => Ok(()),
+            result => result,
+        };
+
+        // Recording the training time
+        if result.is_ok() {
+            set_time_train(
+                &self.arguments.common_arguments.model_path,
+                Some(time.elapsed().as_secs_f64()),
+            )?;
         }
+        call_hooks(&mut callbacks, |callback| callback.on_end(&result))?;
 
         result
     }
 }
 
+impl Runner for TrainRunner {
+    #[inline]
+    fn run(mut self) -> Result<(), Report> {
+        self.train()
+    }
+}
+
 impl fmt::Debug for TrainRunner {
     #[inline]
     fn fmt(
@@ -222,22 +812,69 @@ impl fmt::Debug for TrainRunner {
     }
 }
 
+impl fmt::Display for Stopped {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str("Stopped before the final iteration")
+    }
+}
+
+impl Error for Stopped {}
+
 impl From<&TrainArguments> for Gaussian3dTrainerConfig {
     fn from(arguments: &TrainArguments) -> Self {
         let arguments_increasing_sh_degree_until_iter = arguments
             .increase_sh_degree_from_iter
             + arguments.increase_sh_degree_interval * SH_DEGREE_MAX as u64;
 
+        // NOTE: The other schedules override the constant learning rates at each step.
+        let learning_rate = |schedule: LearningRateSchedule,
+                             start: f64,
+                             end: Option<f64>,
+                             count: Option<u64>| {
+            let config = LearningRateConfig::new(start);
+            match (schedule, end) {
+                (LearningRateSchedule::Exponential, Some(end)) => config
+                    .with_end(end)
+                    .with_count(count.unwrap_or(arguments.iterations)),
+                _ => config,
+            }
+        };
+
         Self::new()
-            .with_learning_rate_colors_sh(LearningRateConfig::new(arguments.feature_lr))
-            .with_learning_rate_opacities(LearningRateConfig::new(arguments.opacity_lr))
-            .with_learning_rate_positions(
-                LearningRateConfig::new(arguments.position_lr_init)
-                    .with_end(arguments.position_lr_final)
-                    .with_count(arguments.position_lr_max_steps),
-            )
-            .with_learning_rate_rotations(LearningRateConfig::new(arguments.rotation_lr))
-            .with_learning_rate_scalings(LearningRateConfig::new(arguments.scaling_lr))
+            .with_learning_rate_colors_sh(learning_rate(
+                arguments.feature_lr_schedule,
+                arguments.feature_lr,
+                arguments.feature_lr_final,
+                arguments.feature_lr_max_steps,
+            ))
+            .with_learning_rate_opacities(learning_rate(
+                arguments.opacity_lr_schedule,
+                arguments.opacity_lr,
+                arguments.opacity_lr_final,
+                arguments.opacity_lr_max_steps,
+            ))
+            .with_learning_rate_positions(learning_rate(
+                arguments.position_lr_schedule,
+                arguments.position_lr_init,
+                Some(arguments.position_lr_final),
+                Some(arguments.position_lr_max_steps),
+            ))
+            .with_learning_rate_rotations(learning_rate(
+                arguments.rotation_lr_schedule,
+                arguments.rotation_lr,
+                arguments.rotation_lr_final,
+                arguments.rotation_lr_max_steps,
+            ))
+            .with_learning_rate_scalings(learning_rate(
+                arguments.scaling_lr_schedule,
+                arguments.scaling_lr,
+                arguments.scaling_lr_final,
+                arguments.scaling_lr_max_steps,
+            ))
             .with_options_renderer(
                 Gaussian3dRenderOptions::new()
                     .with_colors_sh_degree_max(arguments.sh_degree),
@@ -265,3 +902,105 @@ impl From<&TrainArguments> for Gaussian3dTrainerConfig {
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use command::Parser;
+
+    #[test]
+    fn get_points_init_with_seed() {
+        let strategies = ["sphere:count=100", "sphere:count=50,opacity=0.5"]
+            .map(|strategy| strategy.parse::<InitStrategy>().unwrap());
+        let mut cameras = Cameras::default();
+        for (id, position) in (1..).zip([[1.0, 2.0, 3.0], [-1.0, 2.0, 3.0]]) {
+            let mut camera = Camera::default();
+            camera.view.view_position = position;
+            cameras.insert(id, camera);
+        }
+        let points = Points::new();
+        let get_bits = |points: &Points| {
+            points
+                .iter()
+                .map(|point| point.position.map(f64::to_bits))
+                .collect::<Vec<_>>()
+        };
+
+        let (output_1, ranges) =
+            TrainRunner::get_points_init(&strategies, 7, &cameras, &points).unwrap();
+        let (output_2, _) =
+            TrainRunner::get_points_init(&strategies, 7, &cameras, &points).unwrap();
+        let (output_3, _) =
+            TrainRunner::get_points_init(&strategies, 8, &cameras, &points).unwrap();
+
+        assert_eq!(
+            ranges.iter().map(|r| r.0.to_owned()).collect::<Vec<_>>(),
+            [0..100, 100..150]
+        );
+        assert_eq!(get_bits(&output_1), get_bits(&output_2));
+        assert_eq!(format!("{output_1:?}"), format!("{output_2:?}"));
+        assert_ne!(get_bits(&output_1), get_bits(&output_3));
+
+        // NOTE: The points are on the shell around the center of the cameras.
+        let radius_shell = 1.1 * INIT_SPHERE_RADIUS_RATIO;
+        output_1.iter().for_each(|point| {
+            let [x, y, z] = point.position;
+            let distance = (x * x + (y - 2.0).powi(2) + (z - 3.0).powi(2)).sqrt();
+            assert!((distance - radius_shell).abs() < 1e-9, "{distance}");
+        });
+    }
+
+    #[test]
+    fn get_points_init_without_cameras() {
+        let cameras = Cameras::default();
+        let points = Points::new();
+
+        let strategies = ["box:count=10".parse::<InitStrategy>().unwrap()];
+        let output = TrainRunner::get_points_init(&strategies, 7, &cameras, &points);
+        assert!(output.is_err());
+
+        let strategies = ["sphere:count=10".parse::<InitStrategy>().unwrap()];
+        let (output, _) =
+            TrainRunner::get_points_init(&strategies, 7, &cameras, &points).unwrap();
+        assert_eq!(output.len(), 10);
+    }
+
+    #[test]
+    fn get_learning_rate_schedulers() {
+        let arguments = TrainArguments::try_parse_from([
+            "train",
+            "-s",
+            "data",
+            "-m",
+            "model",
+            "--iterations",
+            "1000",
+            "--opacity_lr_schedule",
+            "step",
+            "--lr_milestones",
+            "300",
+            "600",
+            "--position_lr_schedule",
+            "cosine",
+            "--lr_warmup_steps",
+            "100",
+        ])
+        .unwrap();
+
+        let [scheduler_colors_sh, scheduler_opacities, scheduler_positions, ..] =
+            arguments.get_learning_rate_schedulers();
+        assert_eq!(scheduler_colors_sh, None);
+
+        let scheduler = scheduler_opacities.unwrap();
+        assert_eq!(scheduler.start, arguments.opacity_lr);
+        assert_eq!(scheduler.end, arguments.opacity_lr);
+        assert_eq!(scheduler.count, 1000);
+        assert_eq!(scheduler.milestones, vec![300, 600]);
+
+        let scheduler = scheduler_positions.unwrap();
+        assert_eq!(scheduler.start, arguments.position_lr_init);
+        assert_eq!(scheduler.end, arguments.position_lr_final);
+        assert_eq!(scheduler.count, arguments.position_lr_max_steps);
+        assert_eq!(scheduler.warmup, 100);
+    }
+}
@@ -1,12 +1,35 @@
 //! Runner module.
 
+pub mod dataset;
 pub mod gaussian_3d;
+pub mod progress;
+pub mod report;
 
 pub use super::*;
 pub use color_eyre::Report;
 pub use kdam::{Bar, BarBuilder, BarExt};
 
-use std::fmt::Debug;
+use color_eyre::eyre::eyre;
+use std::{
+    error::Error,
+    fmt::{self, Debug},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
+
+/// Exit code when the task is interrupted.
+pub const EXIT_CODE_INTERRUPTED: i32 = 130;
+
+/// It is set when an interruption signal is received.
+static IS_INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// It is set while an [`InterruptGuard`] is alive.
+static IS_INTERRUPTIBLE: AtomicBool = AtomicBool::new(false);
+
+/// Result of installing the handler, which can only be installed once.
+static INTERRUPT_HANDLER: OnceLock<Result<(), String>> = OnceLock::new();
 
 /// Task runner.
 pub trait Runner: Debug {
@@ -14,6 +37,56 @@ pub trait Runner: Debug {
     fn run(self) -> Result<(), Report>;
 }
 
+/// The task is stopped by an interruption signal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Interrupted {
+    /// The last finished iteration.
+    pub iteration: u64,
+}
+
+/// Guard of an interruptible task.
+///
+/// The task is no longer interruptible after the guard is dropped.
+#[derive(Debug)]
+#[must_use = "The task is not interruptible if the guard is dropped"]
+pub struct InterruptGuard(());
+
+/// Install the handler of interruption signals, e.g. SIGINT and SIGTERM,
+/// and make the task interruptible while the returned guard is alive.
+///
+/// The first signal only sets [`is_interrupted`], so the task can stop gracefully.
+/// The second signal, or any signal outside the task, exits immediately.
+///
+/// The handler is installed on the first call, and [`is_interrupted`] is reset
+/// on every call.
+pub fn init_interrupt_handler() -> Result<InterruptGuard, Report> {
+    INTERRUPT_HANDLER
+        .get_or_init(|| {
+            ctrlc::set_handler(|| {
+                if !IS_INTERRUPTIBLE.load(Ordering::SeqCst)
+                    || IS_INTERRUPTED.swap(true, Ordering::SeqCst)
+                {
+                    std::process::exit(EXIT_CODE_INTERRUPTED);
+                }
+                eprintln!("\n| Interrupted | Stopping after the current iteration |");
+            })
+            .map_err(|err| err.to_string())
+        })
+        .to_owned()
+        .map_err(|err| eyre!("The interruption handler cannot be installed: {err}"))?;
+
+    IS_INTERRUPTED.store(false, Ordering::SeqCst);
+    IS_INTERRUPTIBLE.store(true, Ordering::SeqCst);
+
+    Ok(InterruptGuard(()))
+}
+
+/// Return `true` if an interruption signal is received.
+#[inline]
+pub fn is_interrupted() -> bool {
+    IS_INTERRUPTED.load(Ordering::SeqCst)
+}
+
 /// Initialize the progress bar.
 pub fn get_bar() -> Bar {
     // NOTE: The bar format is correct.
@@ -45,3 +118,39 @@ pub fn init_bar(bar: &Bar) {
         _ => false,
     });
 }
+
+impl fmt::Display for Interrupted {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "Interrupted after iteration {}", self.iteration)
+    }
+}
+
+impl Error for Interrupted {}
+
+impl Drop for InterruptGuard {
+    #[inline]
+    fn drop(&mut self) {
+        IS_INTERRUPTIBLE.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_interrupt_handler_twice() {
+        let guard = init_interrupt_handler().unwrap();
+        assert!(IS_INTERRUPTIBLE.load(Ordering::SeqCst));
+        drop(guard);
+        assert!(!IS_INTERRUPTIBLE.load(Ordering::SeqCst));
+
+        IS_INTERRUPTED.store(true, Ordering::SeqCst);
+        let _guard = init_interrupt_handler().unwrap();
+        assert!(!is_interrupted());
+    }
+}
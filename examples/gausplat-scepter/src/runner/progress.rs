@@ -0,0 +1,171 @@
+//! Progress events.
+
+pub use super::*;
+pub use command::gaussian_3d::{Gaussian3dCommonArguments, ProgressFormat};
+
+use serde::Serialize;
+use std::{
+    fmt, fs,
+    io::{self, LineWriter, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Writer of the newline-delimited JSON progress events.
+pub struct ProgressWriter {
+    /// Model directory path of the events.
+    pub model_path: PathBuf,
+    /// Destination of the events.
+    pub writer: Box<dyn Write>,
+}
+
+/// Progress event.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "event")]
+pub enum ProgressEvent {
+    /// The task is started.
+    #[serde(rename = "start")]
+    Start {
+        /// Name of the task.
+        task: String,
+        /// Total number of iterations or images.
+        total: u64,
+    },
+
+    /// Statistics of the training at an iteration.
+    #[serde(rename = "step")]
+    Step {
+        /// The finished iteration.
+        iteration: u64,
+        /// Total number of iterations.
+        total: u64,
+        /// Readable size of the scene.
+        size: String,
+        /// Elapsed time in seconds.
+        elapsed: f64,
+    },
+
+    /// Metrics on the testing dataset.
+    #[serde(rename = "test")]
+    Test {
+        /// The finished iteration.
+        iteration: u64,
+        /// Peak signal-to-noise ratio in dB.
+        psnr: f64,
+        /// Mean structural similarity index.
+        ssim: f64,
+    },
+
+    /// Files are saved.
+    #[serde(rename = "save")]
+    Save {
+        /// The finished iteration.
+        iteration: u64,
+        /// Saved file or directory path.
+        path: PathBuf,
+    },
+
+    /// The task is completed.
+    #[serde(rename = "complete")]
+    Complete {
+        /// Elapsed time in seconds.
+        elapsed: f64,
+    },
+
+    /// The task is failed or stopped.
+    #[serde(rename = "error")]
+    Error {
+        /// Error message.
+        message: String,
+    },
+}
+
+/// Line of a progress event.
+#[derive(Serialize)]
+struct ProgressRecord<'r> {
+    /// Timestamp in seconds since the Unix epoch.
+    time: f64,
+    /// Model directory path.
+    model_path: &'r Path,
+    /// Progress event.
+    #[serde(flatten)]
+    event: &'r ProgressEvent,
+}
+
+impl ProgressWriter {
+    /// Initialize the writer if the progress format is JSON.
+    pub fn init(arguments: &Gaussian3dCommonArguments) -> Result<Option<Self>, Report> {
+        if arguments.progress != ProgressFormat::Json {
+            return Ok(None);
+        }
+
+        // NOTE: The events of the previous tasks are kept in the file.
+        let writer: Box<dyn Write> = match &arguments.progress_file {
+            Some(file_path) => {
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(file_path)?;
+                Box::new(LineWriter::new(file))
+            },
+            None => Box::new(io::stdout()),
+        };
+
+        Ok(Some(Self {
+            model_path: arguments.model_path.to_owned(),
+            writer,
+        }))
+    }
+
+    /// Write the `event` as a line.
+    pub fn write(
+        &mut self,
+        event: ProgressEvent,
+    ) -> Result<(), Report> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let record = ProgressRecord {
+            time,
+            model_path: &self.model_path,
+            event: &event,
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Write the error event of the `report` if the progress format is JSON.
+///
+/// It returns the `report` for the errors before the task is started.
+pub fn write_error<T>(
+    arguments: &Gaussian3dCommonArguments,
+    report: Report,
+) -> Result<T, Report> {
+    let result = ProgressWriter::init(arguments).and_then(|writer| {
+        writer.map_or(Ok(()), |mut writer| {
+            writer.write(ProgressEvent::Error {
+                message: report.to_string(),
+            })
+        })
+    });
+    if let Err(err) = result {
+        log::warn!(target: "gausplat::scepter::progress", "write_error > {err}");
+    }
+    Err(report)
+}
+
+impl fmt::Debug for ProgressWriter {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("ProgressWriter")
+            .field("model_path", &self.model_path)
+            .finish()
+    }
+}
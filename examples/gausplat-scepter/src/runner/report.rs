@@ -0,0 +1,676 @@
+//! Report runner.
+
+pub use super::*;
+pub use command::ReportArguments;
+
+use burn::tensor::TensorData;
+use gausplat::trainer::{
+    metric::{MeanStructuralSimilarity, Metric, Psnr},
+    train::gaussian_3d::{Tensor, Wgpu, WgpuDevice},
+};
+use gaussian_3d::{
+    get_iteration_default, get_time_train,
+    pipeline::{PipelineSummary, SUMMARY_FILE_NAME},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Default method label for the models trained by Gausplat.
+pub const METHOD_GAUSPLAT: &str = "Gausplat-3DGS";
+
+/// Default method label for the models trained by the original 3DGS.
+pub const METHOD_ORIGINAL: &str = "Original-3DGS";
+
+/// Report runner.
+#[derive(Clone)]
+pub struct ReportRunner {
+    /// Arguments for reporting.
+    pub arguments: ReportArguments,
+    /// Entries of the model directories.
+    pub entries: Vec<ReportEntry>,
+}
+
+/// Entry of a model directory.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ReportEntry {
+    /// Method label.
+    pub method: String,
+    /// Scene name.
+    pub scene: String,
+    /// Version of the program.
+    pub version: Option<String>,
+    /// The evaluated iteration.
+    pub iteration: u64,
+    /// Mean structural similarity index.
+    pub ssim: Option<f64>,
+    /// Peak signal-to-noise ratio in dB.
+    pub psnr: Option<f64>,
+    /// Learned perceptual image patch similarity.
+    pub lpips: Option<f64>,
+    /// Training time in seconds.
+    pub time_train: Option<f64>,
+    /// Size of the model file in bytes.
+    pub model_size: Option<u64>,
+    /// Model directory path.
+    pub model_path: PathBuf,
+}
+
+impl ReportArguments {
+    /// Initialize the report runner.
+    pub fn init(&self) -> Result<ReportRunner, Report> {
+        let arguments = self.to_owned();
+
+        let mut entries = Vec::new();
+        for tree_path in &self.tree_paths {
+            let (method, tree_path) = match tree_path.split_once('=') {
+                Some((method, tree_path)) => (Some(method), tree_path),
+                None => (None, tree_path.as_str()),
+            };
+            for model_path in ReportRunner::find_model_paths(tree_path)? {
+                let entry = ReportRunner::read_entry(model_path, method)?;
+                log::info!(
+                    target: "gausplat::scepter::report",
+                    "read {:?} as {} on {}", entry.model_path, entry.method, entry.scene,
+                );
+                entries.push(entry);
+            }
+        }
+
+        Ok(ReportRunner { arguments, entries })
+    }
+}
+
+impl ReportRunner {
+    /// Return the model directories in the tree.
+    ///
+    /// The model directories are not searched further.
+    pub fn find_model_paths(tree_path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Report> {
+        let tree_path = tree_path.as_ref();
+        if tree_path.join("point_cloud").is_dir() {
+            return Ok(vec![tree_path.to_owned()]);
+        }
+
+        let mut paths = fs::read_dir(tree_path)?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut model_paths = Vec::new();
+        for path in paths {
+            model_paths.extend(Self::find_model_paths(path)?);
+        }
+        Ok(model_paths)
+    }
+
+    /// Read the entry of the model directory.
+    ///
+    /// The metrics are read from `summary.json` of Gausplat or `results.json` of the
+    /// original 3DGS. Otherwise, SSIM and PSNR are computed from the rendered images.
+    /// The training time is also read from `time_train.txt` of Gausplat.
+    pub fn read_entry(
+        model_path: impl AsRef<Path>,
+        method: Option<&str>,
+    ) -> Result<ReportEntry, Report> {
+        let model_path = model_path.as_ref();
+
+        // Reading the version and source path
+
+        let version = fs::read_to_string(model_path.join("version.txt"))
+            .ok()
+            .map(|version| version.trim().to_owned());
+        let source_path = ["args-train.json", "args-pipeline.json"]
+            .iter()
+            .find_map(|file_name| {
+                let args = fs::read_to_string(model_path.join(file_name)).ok()?;
+                let args = serde_json::from_str::<Value>(&args).ok()?;
+                Some(Self::find_value(&args, "source_path")?.as_str()?.to_owned())
+            })
+            .or_else(|| {
+                // NOTE: The original 3DGS saves the arguments as a Python namespace.
+                let args = fs::read_to_string(model_path.join("cfg_args")).ok()?;
+                let (_, source_path) = args.split_once("source_path='")?;
+                Some(source_path.split('\'').next()?.to_owned())
+            });
+
+        let method = match method {
+            Some(method) => method.to_owned(),
+            None if version.is_none() && model_path.join("cfg_args").is_file() => {
+                METHOD_ORIGINAL.to_owned()
+            },
+            None => METHOD_GAUSPLAT.to_owned(),
+        };
+        let scene = source_path
+            .as_deref()
+            .and_then(|path| Path::new(path).file_name())
+            .or_else(|| model_path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        // Reading the metrics
+
+        let summary =
+            Self::read_json::<PipelineSummary>(model_path.join(SUMMARY_FILE_NAME))?;
+        let iteration = match &summary {
+            Some(summary) => summary.iteration,
+            None => get_iteration_default(model_path.join("point_cloud"))?,
+        };
+        let model_size = fs::metadata(
+            [
+                model_path,
+                "point_cloud".as_ref(),
+                format!("iteration_{iteration}").as_ref(),
+                "point_cloud.ply".as_ref(),
+            ]
+            .iter()
+            .collect::<PathBuf>(),
+        )
+        .ok()
+        .map(|metadata| metadata.len());
+
+        let mut entry = ReportEntry {
+            method,
+            scene,
+            version,
+            iteration,
+            time_train: get_time_train(model_path)?,
+            model_size,
+            model_path: model_path.to_owned(),
+            ..Default::default()
+        };
+
+        if let Some(summary) = summary {
+            entry.ssim = summary.ssim;
+            entry.psnr = summary.psnr;
+            entry.lpips = summary.lpips;
+            entry.time_train = Some(summary.time_train);
+            return Ok(entry);
+        }
+
+        let results = Self::read_json::<BTreeMap<String, BTreeMap<String, f64>>>(
+            model_path.join("results.json"),
+        )?;
+        if let Some(mut results) = results {
+            if let Some(metrics) = results.remove(&format!("ours_{iteration}")) {
+                entry.ssim = metrics.get("SSIM").copied();
+                entry.psnr = metrics.get("PSNR").copied();
+                entry.lpips = metrics.get("LPIPS").copied();
+                return Ok(entry);
+            }
+        }
+
+        let directory_test = [
+            model_path,
+            "test".as_ref(),
+            format!("ours_{iteration}").as_ref(),
+        ]
+        .iter()
+        .collect::<PathBuf>();
+        if directory_test.is_dir() {
+            let (mssim, psnr) = Self::get_mssim_and_psnr(
+                directory_test.join("renders"),
+                directory_test.join("gt"),
+            )?;
+            entry.ssim = Some(mssim);
+            entry.psnr = Some(psnr);
+        }
+
+        Ok(entry)
+    }
+
+    /// Read the JSON file if it exists.
+    ///
+    /// The file is ignored with a warning if it cannot be deserialized.
+    pub fn read_json<T: DeserializeOwned>(
+        file_path: impl AsRef<Path>
+    ) -> Result<Option<T>, Report> {
+        let file_path = file_path.as_ref();
+        let text = match fs::read_to_string(file_path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(serde_json::from_str::<T>(&text)
+            .map_err(|err| {
+                log::warn!(
+                    target: "gausplat::scepter::report",
+                    "ignore {file_path:?} since it cannot be deserialized: {err}",
+                );
+            })
+            .ok())
+    }
+
+    /// Return the M-SSIM and PSNR metrics of the rendered and true images.
+    ///
+    /// The images are paired by their file names.
+    pub fn get_mssim_and_psnr(
+        directory_rendered: impl AsRef<Path>,
+        directory_true: impl AsRef<Path>,
+    ) -> Result<(f64, f64), Report> {
+        let device = WgpuDevice::default();
+        let metric_psnr = Psnr::init(&device);
+        let metric_mssim = MeanStructuralSimilarity::<Wgpu, 3>::init(&device);
+        let decode = |path: PathBuf| -> Result<Tensor<Wgpu, 3>, Report> {
+            let image = image::open(path)?.into_rgb32f();
+            let (width, height) = image.dimensions();
+            let data =
+                TensorData::new(image.into_raw(), [height as usize, width as usize, 3]);
+            Ok(Tensor::from_data(data, &device).movedim(2, 0))
+        };
+
+        let mut file_names = fs::read_dir(directory_rendered.as_ref())?
+            .filter_map(|entry| Some(entry.ok()?.file_name()))
+            .collect::<Vec<_>>();
+        file_names.sort();
+        if file_names.is_empty() {
+            return Ok((f64::NAN, f64::NAN));
+        }
+
+        // [2] <- [2, 1 * N]
+        let scores_mean = Tensor::cat(
+            file_names
+                .into_iter()
+                .map(|file_name| {
+                    let output = decode(directory_rendered.as_ref().join(&file_name))?;
+                    let target = decode(directory_true.as_ref().join(&file_name))?;
+                    let score = Tensor::stack::<2>(
+                        [
+                            metric_mssim.evaluate(output.to_owned(), target.to_owned()),
+                            metric_psnr.evaluate(output, target),
+                        ]
+                        .into(),
+                        0,
+                    );
+                    Ok(score)
+                })
+                .collect::<Result<Vec<_>, Report>>()?,
+            1,
+        )
+        .mean_dim(1)
+        .into_data();
+
+        // NOTE: The data type is converted.
+        let mut scores_mean = scores_mean
+            .convert::<f64>()
+            .into_vec::<f64>()
+            .unwrap()
+            .into_iter();
+        // NOTE: The index is in bounds.
+        let mssim_mean = scores_mean.next().unwrap();
+        let psnr_mean = scores_mean.next().unwrap();
+
+        Ok((mssim_mean, psnr_mean))
+    }
+
+    /// Return the first value of the `key` in the nested `value`.
+    pub fn find_value<'v>(
+        value: &'v Value,
+        key: &str,
+    ) -> Option<&'v Value> {
+        match value {
+            Value::Object(object) => object.get(key).or_else(|| {
+                object
+                    .values()
+                    .find_map(|value| Self::find_value(value, key))
+            }),
+            Value::Array(array) => {
+                array.iter().find_map(|value| Self::find_value(value, key))
+            },
+            _ => None,
+        }
+    }
+
+    /// Return the Markdown tables.
+    ///
+    /// The layout follows `doc/REPORT.md`.
+    pub fn to_markdown(&self) -> String {
+        let (scenes, rows) = self.get_rows();
+
+        let header = ["Metric", "Method"]
+            .into_iter()
+            .map(str::to_owned)
+            .chain(scenes.iter().cloned())
+            .collect::<Vec<_>>();
+        let widths = header
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                rows.iter()
+                    .map(|row| row[index].chars().count())
+                    .chain([cell.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let line = |cells: &[String]| {
+            let cells = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>();
+            format!("| {} |\n", cells.join(" | "))
+        };
+
+        let mut markdown = line(&header);
+        markdown += &line(
+            &widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>(),
+        );
+        rows.iter().for_each(|row| markdown += &line(row));
+        markdown
+    }
+
+    /// Return the CSV table.
+    ///
+    /// It has a row for each entry.
+    pub fn to_csv(&self) -> String {
+        let escape = |cell: &str| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_owned()
+            }
+        };
+        let number =
+            |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        let mut csv = "method,scene,version,iteration,ssim,psnr,lpips,\
+            time_train_seconds,model_size_bytes,model_path\n"
+            .to_owned();
+        self.entries.iter().for_each(|entry| {
+            let cells = [
+                escape(&entry.method),
+                escape(&entry.scene),
+                escape(entry.version.as_deref().unwrap_or_default()),
+                entry.iteration.to_string(),
+                number(entry.ssim),
+                number(entry.psnr),
+                number(entry.lpips),
+                number(entry.time_train),
+                entry.model_size.map(|v| v.to_string()).unwrap_or_default(),
+                escape(&entry.model_path.to_string_lossy()),
+            ];
+            csv += &cells.join(",");
+            csv += "\n";
+        });
+        csv
+    }
+
+    /// ## Returns
+    ///
+    /// `(scenes, rows)` of the Markdown tables.
+    ///
+    /// Each row is `[metric, method, values...]`.
+    pub fn get_rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let precision = self.arguments.precision;
+        let scenes = self
+            .entries
+            .iter()
+            .map(|entry| entry.scene.to_owned())
+            .collect::<BTreeSet<_>>();
+        let methods = self
+            .entries
+            .iter()
+            .map(|entry| entry.method.to_owned())
+            .collect::<BTreeSet<_>>();
+
+        // NOTE: The later entry overrides the former one of the same method and scene.
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| ((entry.method.as_str(), entry.scene.as_str()), entry))
+            .collect::<BTreeMap<_, _>>();
+
+        type Format = fn(&ReportEntry, usize) -> Option<String>;
+        let metrics: [(&str, Format); 5] = [
+            ("SSIM", |e, p| e.ssim.map(|v| format!("{v:.p$}"))),
+            ("PSNR", |e, p| e.psnr.map(|v| format!("{v:.p$}"))),
+            ("LPIPS", |e, p| e.lpips.map(|v| format!("{v:.p$}"))),
+            ("Tr. Time", |e, _| {
+                e.time_train.map(|v| format!("{:.0}", v / 60.0))
+            }),
+            ("Md. Size", |e, _| {
+                e.model_size.map(|v| format!("{:.0}", v as f64 / 1e6))
+            }),
+        ];
+
+        let mut rows = Vec::new();
+        for (metric, format) in metrics {
+            let rows_metric = methods
+                .iter()
+                .filter_map(|method| {
+                    let values = scenes
+                        .iter()
+                        .map(|scene| {
+                            entries
+                                .get(&(method.as_str(), scene.as_str()))
+                                .and_then(|entry| format(entry, precision))
+                        })
+                        .collect::<Vec<_>>();
+                    // NOTE: The row without any value is skipped.
+                    values.iter().any(Option::is_some).then(|| {
+                        [metric.to_owned(), method.to_owned()]
+                            .into_iter()
+                            .chain(values.into_iter().map(Option::unwrap_or_default))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            rows.extend(rows_metric);
+        }
+
+        (scenes.into_iter().collect(), rows)
+    }
+}
+
+impl Runner for ReportRunner {
+    fn run(self) -> Result<(), Report> {
+        let markdown = self.to_markdown();
+
+        match &self.arguments.output_path {
+            Some(output_path) => {
+                fs::create_dir_all(output_path)?;
+                fs::write(output_path.join("report.md"), markdown)?;
+                fs::write(output_path.join("report.csv"), self.to_csv())?;
+                eprintln!(
+                    "| Reporting | {} models | {output_path:?} |",
+                    self.entries.len()
+                );
+            },
+            None => print!("{markdown}"),
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ReportRunner {
+    #[inline]
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_struct("ReportRunner")
+            .field("arguments", &self.arguments)
+            .field("entries.len()", &self.entries.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use command::Parser;
+    use gaussian_3d::TIME_TRAIN_FILE_NAME;
+
+    fn get_runner(entries: Vec<ReportEntry>) -> ReportRunner {
+        ReportRunner {
+            arguments: ReportArguments::try_parse_from(["report", "models"]).unwrap(),
+            entries,
+        }
+    }
+
+    fn get_entry(
+        method: &str,
+        scene: &str,
+        ssim: Option<f64>,
+    ) -> ReportEntry {
+        ReportEntry {
+            method: method.into(),
+            scene: scene.into(),
+            iteration: 30000,
+            ssim,
+            psnr: ssim.map(|ssim| ssim * 30.0),
+            time_train: Some(600.0),
+            model_size: Some(250_000_000),
+            model_path: format!("models/{scene}").into(),
+            ..Default::default()
+        }
+    }
+
+    fn get_model_path(name: &str) -> PathBuf {
+        let model_path = std::env::temp_dir().join(format!(
+            "gausplat-scepter-report-{name}-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(model_path.join("point_cloud/iteration_7")).unwrap();
+        fs::write(
+            model_path.join("point_cloud/iteration_7/point_cloud.ply"),
+            [0; 9],
+        )
+        .unwrap();
+        model_path
+    }
+
+    #[test]
+    fn find_value() {
+        let value = serde_json::json!({
+            "model": {"gaussian_3d": [{"train": {"source_path": "data/truck"}}]},
+            "source_path": "data/train",
+        });
+        assert_eq!(
+            ReportRunner::find_value(&value, "source_path"),
+            Some(&Value::from("data/train"))
+        );
+
+        let value = serde_json::json!({"model": [{"source_path": "data/truck"}]});
+        assert_eq!(
+            ReportRunner::find_value(&value, "source_path"),
+            Some(&Value::from("data/truck"))
+        );
+        assert_eq!(ReportRunner::find_value(&value, "model_path"), None);
+    }
+
+    #[test]
+    fn to_markdown() {
+        let runner = get_runner(vec![
+            get_entry(METHOD_GAUSPLAT, "truck", Some(0.8)),
+            get_entry(METHOD_ORIGINAL, "truck", Some(0.9)),
+            get_entry(METHOD_GAUSPLAT, "train", None),
+        ]);
+
+        let markdown = runner.to_markdown();
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "| Metric   | Method        | train | truck  |");
+        assert_eq!(lines[1], "| -------- | ------------- | ----- | ------ |");
+        assert_eq!(lines[2], "| SSIM     | Gausplat-3DGS |       | 0.800  |");
+        assert_eq!(lines[3], "| SSIM     | Original-3DGS |       | 0.900  |");
+        assert_eq!(lines[6], "| Tr. Time | Gausplat-3DGS | 10    | 10     |");
+        // NOTE: The LPIPS rows are skipped without any value.
+        assert_eq!(lines.len(), 10);
+        assert!(!markdown.contains("LPIPS"));
+    }
+
+    #[test]
+    fn to_csv() {
+        let mut entry = get_entry(METHOD_GAUSPLAT, "truck", Some(0.8));
+        entry.version = Some("0.1.0, \"dev\"".into());
+        let runner = get_runner(vec![entry, get_entry(METHOD_ORIGINAL, "train", None)]);
+
+        let csv = runner.to_csv();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "method,scene,version,iteration,ssim,psnr,lpips,\
+            time_train_seconds,model_size_bytes,model_path"
+        );
+        assert_eq!(
+            lines[1],
+            "Gausplat-3DGS,truck,\"0.1.0, \"\"dev\"\"\",30000,0.8,24,,\
+            600,250000000,models/truck"
+        );
+        assert_eq!(
+            lines[2],
+            "Original-3DGS,train,,30000,,,,600,250000000,models/train"
+        );
+    }
+
+    #[test]
+    fn read_entry_with_summary() {
+        let model_path = get_model_path("summary");
+        fs::write(model_path.join("version.txt"), "0.1.0\n").unwrap();
+        fs::write(
+            model_path.join("args-train.json"),
+            r#"{"model": {"train": {"source_path": "data/truck"}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            model_path.join(SUMMARY_FILE_NAME),
+            r#"{
+                "iteration": 7, "SSIM": null, "PSNR": 25.5, "LPIPS": null,
+                "time_train": 12.5, "point_count": 3, "model_size": 9,
+                "model_file_path": "point_cloud.ply"
+            }"#,
+        )
+        .unwrap();
+
+        let entry = ReportRunner::read_entry(&model_path, None).unwrap();
+        assert_eq!(entry.method, METHOD_GAUSPLAT);
+        assert_eq!(entry.scene, "truck");
+        assert_eq!(entry.version.as_deref(), Some("0.1.0"));
+        assert_eq!(entry.iteration, 7);
+        assert_eq!(entry.ssim, None);
+        assert_eq!(entry.psnr, Some(25.5));
+        assert_eq!(entry.time_train, Some(12.5));
+        assert_eq!(entry.model_size, Some(9));
+
+        fs::remove_dir_all(&model_path).unwrap();
+    }
+
+    #[test]
+    fn read_entry_with_results_and_time() {
+        let model_path = get_model_path("results");
+        fs::write(
+            model_path.join("cfg_args"),
+            "Namespace(source_path='data/train')",
+        )
+        .unwrap();
+        fs::write(
+            model_path.join("results.json"),
+            r#"{"ours_7": {"SSIM": 0.8, "PSNR": 24.0, "LPIPS": 0.2}}"#,
+        )
+        .unwrap();
+        fs::write(model_path.join(TIME_TRAIN_FILE_NAME), "42.5\n").unwrap();
+        // NOTE: The invalid summary is ignored.
+        fs::write(model_path.join(SUMMARY_FILE_NAME), "{").unwrap();
+
+        let entry = ReportRunner::read_entry(&model_path, Some("Label")).unwrap();
+        assert_eq!(entry.method, "Label");
+        assert_eq!(entry.scene, "train");
+        assert_eq!(entry.iteration, 7);
+        assert_eq!(entry.ssim, Some(0.8));
+        assert_eq!(entry.psnr, Some(24.0));
+        assert_eq!(entry.lpips, Some(0.2));
+        assert_eq!(entry.time_train, Some(42.5));
+
+        fs::remove_dir_all(&model_path).unwrap();
+    }
+}